@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    app_state::AppState,
+    cloud::CloudHitEvent,
+    coin::{Coin, ManualBoostUsedEvent},
+    coin_launch_ui::CoinLaunchSpeedPercentage,
+    fairy::FairyTouchedEvent,
+    game_assets::DataAssets,
+    locale::Strings,
+    player::PlayerId,
+    storage,
+};
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<AchievementDefinitions>()
+            .init_asset_loader::<AchievementDefinitionsLoader>()
+            .add_event::<AchievementUnlockedEvent>()
+            .insert_resource(UnlockedAchievements::load())
+            .insert_resource(RunProgress::default())
+            .add_systems(OnEnter(AppState::Ingame), reset_run_progress)
+            .add_systems(
+                Update,
+                (track_run_progress, check_achievements)
+                    .chain()
+                    .run_if(in_state(AppState::Ingame)),
+            );
+    }
+}
+
+#[derive(Deserialize)]
+pub enum AchievementCondition {
+    /// Reach the given altitude (in metres) without touching a cloud.
+    NoCloudHitUntil(f32),
+    /// Reach the given altitude (in metres) without using a manual boost.
+    NoManualBoostUntil(f32),
+    /// Touch `count` fairies, with the first and last touch at most `seconds` apart.
+    FairyChain { count: usize, seconds: f32 },
+    /// Launch the coin with at least the given launch bar percentage.
+    PerfectLaunch(f32),
+}
+
+#[derive(Deserialize)]
+pub struct AchievementDefinition {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub condition: AchievementCondition,
+}
+
+#[derive(Deserialize, TypeUuid, TypePath)]
+#[uuid = "6f1c0a8e-3d52-4b8a-9c4e-1a7f5e2b9d31"]
+#[serde(transparent)]
+pub struct AchievementDefinitions(pub Vec<AchievementDefinition>);
+
+#[derive(Default)]
+struct AchievementDefinitionsLoader;
+
+impl AssetLoader for AchievementDefinitionsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let definitions = ron::de::from_bytes::<AchievementDefinitions>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(definitions));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["achievements.ron"]
+    }
+}
+
+/// Text of an achievement in the current language, `field` being "name" or "description".
+/// The definitions hold the English texts, translations add `achievement.<id>.<field>` keys.
+pub fn achievement_text<'a>(
+    strings: &'a Strings,
+    id: &str,
+    field: &str,
+    english: &'a str,
+) -> &'a str {
+    strings.get_or(&format!("achievement.{}.{}", id, field), english)
+}
+
+#[derive(Event)]
+pub struct AchievementUnlockedEvent {
+    pub id: String,
+    pub name: String,
+}
+
+const ACHIEVEMENTS_SAVE_KEY: &str = "achievements";
+
+#[derive(Resource)]
+pub struct UnlockedAchievements(HashSet<String>);
+
+impl UnlockedAchievements {
+    fn load() -> Self {
+        Self(storage::load(ACHIEVEMENTS_SAVE_KEY).unwrap_or_default())
+    }
+
+    fn save(&self) {
+        storage::save(ACHIEVEMENTS_SAVE_KEY, &self.0);
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.0.contains(id)
+    }
+}
+
+/// Per-player progress, so each racer earns achievements on their own flight.
+#[derive(Resource, Default)]
+struct RunProgress(HashMap<PlayerId, PlayerRunProgress>);
+
+#[derive(Default)]
+struct PlayerRunProgress {
+    cloud_hit: bool,
+    manual_boost_used: bool,
+    fairy_touch_times: Vec<f32>,
+}
+
+impl AchievementCondition {
+    fn is_met(&self, progress: &PlayerRunProgress, altitude: f32, launch_percentage: f32) -> bool {
+        match *self {
+            AchievementCondition::NoCloudHitUntil(target) => {
+                !progress.cloud_hit && altitude >= target
+            }
+            AchievementCondition::NoManualBoostUntil(target) => {
+                !progress.manual_boost_used && altitude >= target
+            }
+            AchievementCondition::FairyChain { count, seconds } => {
+                count > 0
+                    && progress
+                        .fairy_touch_times
+                        .windows(count)
+                        .any(|window| window[count - 1] - window[0] <= seconds)
+            }
+            AchievementCondition::PerfectLaunch(threshold) => launch_percentage >= threshold,
+        }
+    }
+}
+
+fn reset_run_progress(mut progress: ResMut<RunProgress>) {
+    *progress = Default::default();
+}
+
+fn track_run_progress(
+    time: Res<Time>,
+    mut progress: ResMut<RunProgress>,
+    mut cloud_hit_events: EventReader<CloudHitEvent>,
+    mut boost_used_events: EventReader<ManualBoostUsedEvent>,
+    mut fairy_touched_events: EventReader<FairyTouchedEvent>,
+) {
+    cloud_hit_events.iter().for_each(|event| {
+        progress.0.entry(event.player).or_default().cloud_hit = true;
+    });
+
+    boost_used_events.iter().for_each(|event| {
+        progress
+            .0
+            .entry(event.player)
+            .or_default()
+            .manual_boost_used = true;
+    });
+
+    fairy_touched_events.iter().for_each(|event| {
+        progress
+            .0
+            .entry(event.player)
+            .or_default()
+            .fairy_touch_times
+            .push(time.elapsed_seconds());
+    });
+}
+
+fn check_achievements(
+    data_assets: Res<DataAssets>,
+    definitions: Res<Assets<AchievementDefinitions>>,
+    progress: Res<RunProgress>,
+    launch_speed_percentage: Res<CoinLaunchSpeedPercentage>,
+    coin_query: Query<(&Coin, &PlayerId)>,
+    mut unlocked: ResMut<UnlockedAchievements>,
+    mut unlocked_events: EventWriter<AchievementUnlockedEvent>,
+) {
+    let definitions = match definitions.get(&data_assets.achievements) {
+        Some(definitions) => definitions,
+        None => return,
+    };
+    let no_progress = PlayerRunProgress::default();
+    let mut any_unlocked = false;
+
+    coin_query.for_each(|(coin, player)| {
+        let altitude = coin.highest_altitude_recorded / 10.0;
+        let player_progress = progress.0.get(player).unwrap_or(&no_progress);
+
+        definitions.0.iter().for_each(|definition| {
+            if unlocked.is_unlocked(&definition.id)
+                || !definition.condition.is_met(
+                    player_progress,
+                    altitude,
+                    launch_speed_percentage.0,
+                )
+            {
+                return;
+            }
+
+            unlocked.0.insert(definition.id.clone());
+            unlocked_events.send(AchievementUnlockedEvent {
+                id: definition.id.clone(),
+                name: definition.name.clone(),
+            });
+            any_unlocked = true;
+        });
+    });
+
+    if any_unlocked {
+        unlocked.save();
+    }
+}
@@ -0,0 +1,191 @@
+use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+
+use crate::{
+    achievements::{
+        achievement_text, AchievementDefinitions, AchievementUnlockedEvent, UnlockedAchievements,
+    },
+    app_state::{AppState, StateOwner},
+    display::fit_projection,
+    game_assets::{DataAssets, FontAssets},
+    locale::Strings,
+    theme::Theme,
+    transition::ScreenTransition,
+};
+
+pub struct AchievementsUiPlugin;
+
+impl Plugin for AchievementsUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Achievements), setup_achievements_ui)
+            .add_systems(
+                Update,
+                return_to_main_menu.run_if(in_state(AppState::Achievements)),
+            )
+            .add_systems(
+                Update,
+                (show_achievement_toasts, expire_achievement_toasts)
+                    .run_if(in_state(AppState::Ingame)),
+            );
+    }
+}
+
+fn setup_achievements_ui(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    data_assets: Res<DataAssets>,
+    definitions: Res<Assets<AchievementDefinitions>>,
+    unlocked: Res<UnlockedAchievements>,
+) {
+    commands.spawn((
+        Camera2dBundle {
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::Custom(theme.colors.background),
+            },
+            projection: fit_projection(),
+            ..Default::default()
+        },
+        StateOwner(AppState::Achievements),
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(24.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            StateOwner(AppState::Achievements),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                strings.get("achievements.title"),
+                TextStyle {
+                    font_size: theme.font_size(48.0),
+                    color: theme.colors.title,
+                    ..Default::default()
+                },
+            ));
+
+            definitions
+                .get(&data_assets.achievements)
+                .iter()
+                .flat_map(|definitions| definitions.0.iter())
+                .for_each(|definition| {
+                    let is_unlocked = unlocked.is_unlocked(&definition.id);
+
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn(TextBundle::from_section(
+                                achievement_text(
+                                    &strings,
+                                    &definition.id,
+                                    "name",
+                                    &definition.name,
+                                ),
+                                theme.text_style(
+                                    &font_assets.font_fira,
+                                    32.0,
+                                    if is_unlocked {
+                                        theme.colors.accent
+                                    } else {
+                                        theme.colors.muted
+                                    },
+                                ),
+                            ));
+                            parent.spawn(TextBundle::from_section(
+                                achievement_text(
+                                    &strings,
+                                    &definition.id,
+                                    "description",
+                                    &definition.description,
+                                ),
+                                theme.text_style(&font_assets.font_fira, 16.0, theme.colors.text),
+                            ));
+                        });
+                });
+
+            parent.spawn(TextBundle::from_section(
+                strings.get("achievements.return"),
+                theme.text_style(&font_assets.font_fira, 32.0, theme.colors.positive),
+            ));
+        });
+}
+
+fn return_to_main_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        transition.go_to(AppState::MainMenu);
+    }
+}
+
+#[derive(Component)]
+struct AchievementToast(Timer);
+
+const TOAST_DURATION_SECS: f32 = 3.0;
+const TOAST_HEIGHT: f32 = 40.0;
+
+fn show_achievement_toasts(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    mut unlocked_events: EventReader<AchievementUnlockedEvent>,
+    toast_query: Query<(), With<AchievementToast>>,
+) {
+    let mut toast_count = toast_query.iter().count();
+
+    unlocked_events.iter().for_each(|event| {
+        commands.spawn((
+            TextBundle::from_section(
+                strings.format(
+                    "achievements.unlocked",
+                    &[(
+                        "name",
+                        &achievement_text(&strings, &event.id, "name", &event.name),
+                    )],
+                ),
+                theme.text_style(&font_assets.font_fira, 28.0, theme.colors.accent),
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(16.0 + toast_count as f32 * TOAST_HEIGHT),
+                right: Val::Px(16.0),
+                ..Default::default()
+            }),
+            AchievementToast(Timer::from_seconds(TOAST_DURATION_SECS, TimerMode::Once)),
+            StateOwner(AppState::Ingame),
+        ));
+        toast_count += 1;
+    });
+}
+
+fn expire_achievement_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(&mut AchievementToast, Entity)>,
+) {
+    query.for_each_mut(|(mut toast, entity)| {
+        if toast.0.tick(time.delta()).finished() {
+            commands.get_entity(entity).unwrap().despawn();
+        }
+    });
+}
@@ -1,44 +1,408 @@
 use bevy::prelude::*;
 
+use crate::{math::lerp_color, theme::Theme};
+
 pub struct AnimPlugin;
 
 impl Plugin for AnimPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, animate_scale);
+        app.add_event::<TweenCompleted>()
+            .add_systems(
+                Update,
+                (
+                    advance_tweens,
+                    (
+                        apply_transform_tweens,
+                        apply_sprite_tweens,
+                        apply_atlas_sprite_tweens,
+                        apply_text_tweens,
+                        apply_background_tweens,
+                        apply_style_tweens,
+                    ),
+                    remove_completed_tweens,
+                )
+                    .chain()
+                    .in_set(TweenSystems),
+            )
+            .add_systems(Update, animate_frames);
+    }
+}
+
+/// Systems advancing and applying the tweens; run after it to replace a completed `Tween`.
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TweenSystems;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Ease {
+    Linear,
+    QuadOut,
+    QuadInOut,
+    CubicOut,
+    SineInOut,
+    /// Overshoots the end value a little before settling.
+    BackOut,
+}
+
+impl Ease {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Ease::Linear => t,
+            Ease::QuadOut => t * (2.0 - t),
+            Ease::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    -1.0 + (4.0 - 2.0 * t) * t
+                }
+            }
+            Ease::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Ease::SineInOut => -((std::f32::consts::PI * t).cos() - 1.0) / 2.0,
+            Ease::BackOut => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum TweenTarget {
+    Translation(Vec3, Vec3),
+    Scale(Vec3, Vec3),
+    Rotation(Quat, Quat),
+    /// Colour of a `Sprite` or `TextureAtlasSprite`.
+    SpriteColor(Color, Color),
+    /// Colour of every section of a `Text`.
+    TextColor(Color, Color),
+    BackgroundColor(Color, Color),
+    /// `Style::left` and `Style::top`, in pixels.
+    UiOffset(Vec2, Vec2),
+}
+
+impl TweenTarget {
+    fn is_motion(&self) -> bool {
+        matches!(
+            self,
+            TweenTarget::Translation(..)
+                | TweenTarget::Scale(..)
+                | TweenTarget::Rotation(..)
+                | TweenTarget::UiOffset(..)
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TweenMode {
+    Once,
+    Loop,
+    PingPong,
+}
+
+struct TweenStep {
+    targets: Vec<TweenTarget>,
+    duration: f32,
+    ease: Ease,
+    delay: f32,
+}
+
+impl TweenStep {
+    fn length(&self) -> f32 {
+        self.delay + self.duration
     }
 }
 
+/// Animates one or more properties of the entity; steps run one after another, and the targets
+/// of a single step run together.
 #[derive(Component)]
-pub struct AnimScale {
-    scale: f32,
+pub struct Tween {
+    steps: Vec<TweenStep>,
+    mode: TweenMode,
+    elapsed: f32,
     direction: f32,
+    completed: bool,
 }
 
-impl Default for AnimScale {
-    fn default() -> Self {
+/// Sent when a `TweenMode::Once` tween reaches its end, right before the `Tween` is removed.
+#[derive(Event)]
+pub struct TweenCompleted {
+    pub entity: Entity,
+}
+
+/// Despawns the entity (recursively) once its tween has completed.
+#[derive(Component)]
+pub struct DespawnOnTweenCompleted;
+
+impl Tween {
+    pub fn new(target: TweenTarget, duration: f32, ease: Ease) -> Self {
         Self {
-            scale: 1.0,
+            steps: vec![TweenStep {
+                targets: vec![target],
+                duration,
+                ease,
+                delay: 0.0,
+            }],
+            mode: TweenMode::Once,
+            elapsed: 0.0,
             direction: 1.0,
+            completed: false,
+        }
+    }
+
+    /// Scales back and forth between `min` and `max`, e.g. to make pickups stand out.
+    pub fn pulse(min: f32, max: f32, duration: f32) -> Self {
+        Self::new(
+            TweenTarget::Scale(Vec3::new(min, min, 1.0), Vec3::new(max, max, 1.0)),
+            duration,
+            Ease::SineInOut,
+        )
+        .with_mode(TweenMode::PingPong)
+    }
+
+    /// Animates `target` at the same time as the last step.
+    pub fn and(mut self, target: TweenTarget) -> Self {
+        self.last_step().targets.push(target);
+        self
+    }
+
+    /// Adds a step that starts once the previous one is over.
+    pub fn then(mut self, target: TweenTarget, duration: f32, ease: Ease) -> Self {
+        self.steps.push(TweenStep {
+            targets: vec![target],
+            duration,
+            ease,
+            delay: 0.0,
+        });
+        self
+    }
+
+    /// Waits before starting the last step.
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.last_step().delay = delay;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: TweenMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn last_step(&mut self) -> &mut TweenStep {
+        self.steps.last_mut().unwrap()
+    }
+
+    fn total_duration(&self) -> f32 {
+        self.steps.iter().map(TweenStep::length).sum()
+    }
+
+    fn advance(&mut self, delta: f32) -> bool {
+        let total = self.total_duration();
+        self.elapsed += delta * self.direction;
+
+        match self.mode {
+            TweenMode::Once => {
+                if self.elapsed >= total {
+                    self.elapsed = total;
+
+                    if !self.completed {
+                        self.completed = true;
+                        return true;
+                    }
+                }
+            }
+            TweenMode::Loop => {
+                if total > 0.0 {
+                    self.elapsed %= total;
+                }
+            }
+            TweenMode::PingPong => {
+                if self.elapsed >= total {
+                    self.elapsed = total - (self.elapsed - total);
+                    self.direction = -1.0;
+                } else if self.elapsed <= 0.0 {
+                    self.elapsed = -self.elapsed;
+                    self.direction = 1.0;
+                }
+            }
         }
+
+        false
+    }
+
+    /// The targets that have started so far, with their eased progress.
+    ///
+    /// With `reduced_motion`, moving targets jump to their end, or stay put if the tween loops.
+    fn samples(&self, reduced_motion: bool) -> impl Iterator<Item = (&TweenTarget, f32)> {
+        let mut start = 0.0;
+
+        self.steps
+            .iter()
+            .take_while(move |step| {
+                let started = start <= self.elapsed;
+                start += step.length();
+                started
+            })
+            .scan(0.0, move |step_start, step| {
+                let local = self.elapsed - *step_start - step.delay;
+                *step_start += step.length();
+
+                let t = if step.duration > 0.0 {
+                    local / step.duration
+                } else {
+                    1.0
+                };
+                Some((step, step.ease.apply(t)))
+            })
+            .flat_map(|(step, t)| step.targets.iter().map(move |target| (target, t)))
+            .filter_map(move |(target, t)| {
+                if !reduced_motion || !target.is_motion() {
+                    Some((target, t))
+                } else if self.mode == TweenMode::Once {
+                    Some((target, 1.0))
+                } else {
+                    None
+                }
+            })
     }
 }
 
-fn animate_scale(time: Res<Time>, mut query: Query<(&mut Transform, &mut AnimScale)>) {
-    query.for_each_mut(|(mut transform, mut anim)| {
-        anim.scale += anim.direction * time.delta_seconds();
+fn advance_tweens(
+    time: Res<Time>,
+    mut query: Query<(&mut Tween, Entity)>,
+    mut completed_events: EventWriter<TweenCompleted>,
+) {
+    query.for_each_mut(|(mut tween, entity)| {
+        if tween.advance(time.delta_seconds()) {
+            completed_events.send(TweenCompleted { entity });
+        }
+    });
+}
+
+fn apply_transform_tweens(theme: Res<Theme>, mut query: Query<(&Tween, &mut Transform)>) {
+    query.for_each_mut(|(tween, mut transform)| {
+        tween
+            .samples(theme.reduced_motion)
+            .for_each(|(target, t)| match *target {
+                TweenTarget::Translation(start, end) => transform.translation = start.lerp(end, t),
+                TweenTarget::Scale(start, end) => transform.scale = start.lerp(end, t),
+                TweenTarget::Rotation(start, end) => transform.rotation = start.slerp(end, t),
+                _ => {}
+            });
+    });
+}
+
+fn apply_sprite_tweens(mut query: Query<(&Tween, &mut Sprite)>) {
+    query.for_each_mut(|(tween, mut sprite)| {
+        tween.samples(false).for_each(|(target, t)| {
+            if let TweenTarget::SpriteColor(start, end) = *target {
+                sprite.color = lerp_color(start, end, t);
+            }
+        });
+    });
+}
+
+fn apply_atlas_sprite_tweens(mut query: Query<(&Tween, &mut TextureAtlasSprite)>) {
+    query.for_each_mut(|(tween, mut sprite)| {
+        tween.samples(false).for_each(|(target, t)| {
+            if let TweenTarget::SpriteColor(start, end) = *target {
+                sprite.color = lerp_color(start, end, t);
+            }
+        });
+    });
+}
+
+fn apply_text_tweens(mut query: Query<(&Tween, &mut Text)>) {
+    query.for_each_mut(|(tween, mut text)| {
+        tween.samples(false).for_each(|(target, t)| {
+            if let TweenTarget::TextColor(start, end) = *target {
+                let color = lerp_color(start, end, t);
+                text.sections
+                    .iter_mut()
+                    .for_each(|section| section.style.color = color);
+            }
+        });
+    });
+}
+
+fn apply_background_tweens(mut query: Query<(&Tween, &mut BackgroundColor)>) {
+    query.for_each_mut(|(tween, mut background)| {
+        tween.samples(false).for_each(|(target, t)| {
+            if let TweenTarget::BackgroundColor(start, end) = *target {
+                background.0 = lerp_color(start, end, t);
+            }
+        });
+    });
+}
 
-        if anim.direction < 0.0 {
-            if anim.scale < 0.8 {
-                anim.scale = 0.8;
-                anim.direction = 1.0;
+fn apply_style_tweens(theme: Res<Theme>, mut query: Query<(&Tween, &mut Style)>) {
+    query.for_each_mut(|(tween, mut style)| {
+        tween.samples(theme.reduced_motion).for_each(|(target, t)| {
+            if let TweenTarget::UiOffset(start, end) = *target {
+                let offset = start.lerp(end, t);
+                style.left = Val::Px(offset.x);
+                style.top = Val::Px(offset.y);
             }
-        } else {
-            if anim.scale > 1.2 {
-                anim.scale = 1.2;
-                anim.direction = -1.0;
+        });
+    });
+}
+
+/// Completed tweens leave their end values in place and are removed, unless the entity should go.
+fn remove_completed_tweens(
+    mut commands: Commands,
+    mut completed_events: EventReader<TweenCompleted>,
+    query: Query<Option<&DespawnOnTweenCompleted>, With<Tween>>,
+) {
+    completed_events.iter().for_each(|event| {
+        match query.get(event.entity) {
+            Ok(Some(_)) => commands.entity(event.entity).despawn_recursive(),
+            Ok(None) => {
+                commands.entity(event.entity).remove::<Tween>();
             }
+            Err(_) => {}
+        };
+    });
+}
+
+/// Steps through the frames of a `TextureAtlasSprite`, looping.
+#[derive(Component)]
+pub struct FrameAnimation {
+    pub frame_count: usize,
+    /// Frames per second, may be changed on the fly (e.g. to follow the coin speed).
+    pub fps: f32,
+    progress: f32,
+}
+
+impl FrameAnimation {
+    pub fn new(frame_count: usize, fps: f32) -> Self {
+        Self {
+            frame_count,
+            fps,
+            progress: 0.0,
+        }
+    }
+}
+
+fn animate_frames(
+    time: Res<Time>,
+    mut query: Query<(
+        &mut TextureAtlasSprite,
+        &mut FrameAnimation,
+        &ComputedVisibility,
+    )>,
+) {
+    query.for_each_mut(|(mut sprite, mut anim, visibility)| {
+        // picks up where it was when it comes back into view
+        if !visibility.is_visible() {
+            return;
         }
 
-        transform.scale = Vec3::new(anim.scale, anim.scale, 1.0);
+        anim.progress = (anim.progress + anim.fps * time.delta_seconds()) % anim.frame_count as f32;
+
+        let index = anim.progress as usize;
+        if sprite.index != index {
+            sprite.index = index;
+        }
     });
 }
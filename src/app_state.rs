@@ -10,6 +10,25 @@ pub enum AppState {
     CoinLaunch,
     Ingame,
     End,
+    Shop,
+    Achievements,
+    Settings,
+}
+
+/// Part of `AppState::Ingame` the flight is in.
+#[derive(Default, States, Debug, Hash, Eq, PartialEq, Clone, Copy)]
+pub enum FlightPhase {
+    #[default]
+    Flying,
+    /// A coin is out, but time slows down to give it a last chance.
+    Rescue,
+    /// The flight is over and the coins fall back to the launcher.
+    Landing,
+}
+
+/// Run condition for the gameplay, which stops once the coins fall back.
+pub fn in_flight(phase: Res<State<FlightPhase>>) -> bool {
+    *phase.get() != FlightPhase::Landing
 }
 
 #[derive(Component)]
@@ -18,11 +37,18 @@ pub struct StateOwner(pub AppState);
 impl Plugin for AppStatePlugin {
     fn build(&self, app: &mut App) {
         app.add_state::<AppState>()
+            .add_state::<FlightPhase>()
             .add_systems(OnExit(AppState::Loading), remove_loading)
             .add_systems(OnExit(AppState::MainMenu), remove_main_menu)
             .add_systems(OnExit(AppState::CoinLaunch), remove_coin_launch)
-            .add_systems(OnExit(AppState::Ingame), remove_ingame)
-            .add_systems(OnExit(AppState::End), remove_end);
+            .add_systems(
+                OnExit(AppState::Ingame),
+                (remove_ingame, reset_flight_phase),
+            )
+            .add_systems(OnExit(AppState::End), remove_end)
+            .add_systems(OnExit(AppState::Shop), remove_shop)
+            .add_systems(OnExit(AppState::Achievements), remove_achievements)
+            .add_systems(OnExit(AppState::Settings), remove_settings);
     }
 }
 
@@ -54,6 +80,22 @@ fn remove_ingame(mut commands: Commands, query: Query<(Entity, &StateOwner)>) {
     remove_entities(&mut commands, &query, AppState::Ingame);
 }
 
+fn reset_flight_phase(mut next_phase: ResMut<NextState<FlightPhase>>) {
+    next_phase.set(FlightPhase::Flying);
+}
+
 fn remove_end(mut commands: Commands, query: Query<(Entity, &StateOwner)>) {
     remove_entities(&mut commands, &query, AppState::End);
 }
+
+fn remove_shop(mut commands: Commands, query: Query<(Entity, &StateOwner)>) {
+    remove_entities(&mut commands, &query, AppState::Shop);
+}
+
+fn remove_achievements(mut commands: Commands, query: Query<(Entity, &StateOwner)>) {
+    remove_entities(&mut commands, &query, AppState::Achievements);
+}
+
+fn remove_settings(mut commands: Commands, query: Query<(Entity, &StateOwner)>) {
+    remove_entities(&mut commands, &query, AppState::Settings);
+}
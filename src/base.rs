@@ -1,4 +1,6 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, window::WindowResizeConstraints};
+
+use crate::display::{PLAYFIELD_HEIGHT, PLAYFIELD_WIDTH};
 
 pub struct CorePlugin;
 
@@ -7,9 +9,16 @@ impl Plugin for CorePlugin {
         app.add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
                 title: "Coin in the Sky".to_string(),
-                resolution: (960.0, 720.0).into(),
+                resolution: (PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT).into(),
+                resize_constraints: WindowResizeConstraints {
+                    min_width: PLAYFIELD_WIDTH * 0.5,
+                    min_height: PLAYFIELD_HEIGHT * 0.5,
+                    ..Default::default()
+                },
                 // wasm: ID of the element to bind to
                 canvas: Some("#bevy".to_owned()),
+                // wasm: follow the size of the page, which the canvas fills
+                fit_canvas_to_parent: true,
                 // wasm: tells wasm not to override default event handling, like F5 and Ctrl+R
                 prevent_default_event_handling: false,
                 ..Default::default()
@@ -1,11 +1,12 @@
 use bevy::prelude::*;
 
 use crate::{
-    anim::AnimScale,
-    app_state::{AppState, StateOwner},
+    anim::{FrameAnimation, Tween},
+    app_state::{in_flight, AppState},
     coin::Coin,
-    game_assets::TextureAssets,
     physics::RelativeCoinY,
+    player::PlayerId,
+    pool::{PoolKind, PoolSpawner, Pools},
 };
 
 pub struct BoostItemPlugin;
@@ -14,17 +15,19 @@ impl Plugin for BoostItemPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (init_boost_items, check_boost_item_coin_collision).run_if(in_state(AppState::Ingame)),
+            check_boost_item_coin_collision
+                .run_if(in_state(AppState::Ingame))
+                .run_if(in_flight),
         );
     }
 }
 
-#[derive(Component)]
-pub struct InitBoostItem(pub Vec2);
-
 #[derive(Component)]
 pub struct BoostItem;
 
+const BOOST_ITEM_SHIMMER_FRAMES: usize = 4;
+const BOOST_ITEM_SHIMMER_FPS: f32 = 6.0;
+
 const BOOST_ITEM_SIZE: Vec2 = Vec2::new(40.0, 40.0);
 
 impl BoostItem {
@@ -36,43 +39,52 @@ impl BoostItem {
     }
 }
 
-fn init_boost_items(
-    mut commands: Commands,
-    query: Query<(&InitBoostItem, Entity)>,
-    texture_assets: Res<TextureAssets>,
-) {
-    query.for_each(|(init_item, init_item_entity)| {
-        let pos = Vec3::new(init_item.0.x, init_item.0.y, 0.0);
-
-        commands.get_entity(init_item_entity).unwrap().despawn();
-        commands.spawn((
-            SpriteBundle {
-                texture: texture_assets.texture_boost.clone(),
-                transform: Transform::from_translation(pos),
+pub fn spawn_boost_item(spawner: &mut PoolSpawner, position: Vec2, player: PlayerId) {
+    let bundle = (
+        SpriteSheetBundle {
+            texture_atlas: spawner.texture_assets.atlas_boost_shimmer.clone(),
+            sprite: TextureAtlasSprite {
+                color: spawner.theme.colors.pickup,
                 ..Default::default()
             },
-            RelativeCoinY,
-            BoostItem,
-            AnimScale::default(),
-            StateOwner(AppState::Ingame),
-        ));
-    });
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..Default::default()
+        },
+        FrameAnimation::new(BOOST_ITEM_SHIMMER_FRAMES, BOOST_ITEM_SHIMMER_FPS),
+        RelativeCoinY,
+        BoostItem,
+        Tween::pulse(0.8, 1.2, 0.4),
+        player,
+        player.render_layers(),
+    );
+
+    let (entity, _) = match spawner.acquire(PoolKind::BoostItem, player) {
+        Some(acquired) => acquired,
+        None => return,
+    };
+    spawner.commands.entity(entity).insert(bundle);
 }
 
 fn check_boost_item_coin_collision(
     mut commands: Commands,
-    mut coin_query: Query<(&mut Coin, &Transform)>,
-    item_query: Query<(&Transform, Entity), (With<BoostItem>, Without<Coin>)>,
+    mut pools: ResMut<Pools>,
+    mut coin_query: Query<(&mut Coin, &Transform, &PlayerId)>,
+    item_query: Query<(&Transform, &PlayerId, Entity), With<BoostItem>>,
 ) {
-    let (mut coin, coin_transform) = coin_query.single_mut();
-    let coin_rect = Coin::get_bounds(coin_transform);
+    coin_query.for_each_mut(|(mut coin, coin_transform, coin_player)| {
+        let coin_rect = Coin::get_bounds(coin_transform);
+
+        item_query.for_each(|(item_transform, player, item_entity)| {
+            if player != coin_player {
+                return;
+            }
 
-    item_query.for_each(|(item_transform, item_entity)| {
-        let item_rect = BoostItem::get_bounds(item_transform);
+            let item_rect = BoostItem::get_bounds(item_transform);
 
-        if !item_rect.intersect(coin_rect).is_empty() {
-            coin.additional_boosts += 1;
-            commands.get_entity(item_entity).unwrap().despawn();
-        }
+            if !item_rect.intersect(coin_rect).is_empty() {
+                coin.additional_boosts += 1;
+                pools.release(&mut commands, item_entity, PoolKind::BoostItem, *player);
+            }
+        });
     });
 }
@@ -2,123 +2,257 @@ use bevy::prelude::*;
 use rand::Rng;
 
 use crate::{
-    app_state::{AppState, StateOwner},
+    anim::{DespawnOnTweenCompleted, Ease, Tween, TweenTarget},
+    app_state::{in_flight, AppState},
     coin::Coin,
-    game_assets::TextureAssets,
+    game_assets::FontAssets,
+    locale::Strings,
+    modifiers::{ActiveModifiers, Modifier},
     physics::RelativeCoinY,
+    player::{for_player, PlayerId},
+    pool::{PoolKind, PoolSpawner},
+    theme::Theme,
+    upgrades::Upgrades,
 };
 
 pub struct CloudPlugin;
 
 impl Plugin for CloudPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (init_clouds, check_cloud_coin_collision).run_if(in_state(AppState::Ingame)),
-        );
+        app.add_event::<CloudHitEvent>()
+            .add_event::<NearMissEvent>()
+            .add_systems(
+                Update,
+                (
+                    check_cloud_coin_collision.run_if(in_flight),
+                    reveal_nearby_clouds,
+                    spawn_near_miss_labels,
+                )
+                    .run_if(in_state(AppState::Ingame)),
+            );
     }
 }
 
-#[derive(Component)]
-pub struct InitCloud(pub Vec2);
+#[derive(Event)]
+pub struct CloudHitEvent {
+    pub player: PlayerId,
+}
+
+/// Sent when a coin flies past a cloud within `NEAR_MISS_MARGIN` without touching it.
+#[derive(Event)]
+pub struct NearMissEvent {
+    pub player: PlayerId,
+}
 
 #[derive(Component)]
-struct Cloud {
+pub struct Cloud {
     active: bool,
+    /// The coin came within the near miss margin of this cloud.
+    grazed: bool,
+    /// The coin is past this cloud, so it can no longer count as a near miss.
+    passed: bool,
 }
 
 impl Default for Cloud {
     fn default() -> Self {
-        Self { active: true }
+        Self {
+            active: true,
+            grazed: false,
+            passed: false,
+        }
     }
 }
 
 const CLOUD_SIZE: Vec2 = Vec2::new(100.0, 30.0);
+const NEAR_MISS_MARGIN: f32 = 24.0;
 
 impl Cloud {
+    /// Whether the cloud can still be hit; a cloud only slows the coin down once.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
     pub fn get_bounds(transform: &Transform) -> Rect {
         Rect::from_center_size(
             Vec2::new(transform.translation.x, transform.translation.y),
             CLOUD_SIZE,
         )
     }
+
+    pub fn get_near_miss_bounds(transform: &Transform) -> Rect {
+        Self::get_bounds(transform).inset(NEAR_MISS_MARGIN)
+    }
 }
 
 const CLOUD_SPRITE_TOTAL: i32 = 8;
 const CLOUD_SPRITE_PER_ROW_COUNT: i32 = 2;
 const CLOUD_SPRITE_SIZE: Vec2 = Vec2::new(128.0, 64.0);
 
-fn init_clouds(
-    mut commands: Commands,
-    query: Query<(&InitCloud, Entity)>,
-    texture_assets: Res<TextureAssets>,
-) {
-    if query.is_empty() {
-        return;
-    }
+/// Spawns a cloud with one of the cloud sprites picked at random.
+pub fn spawn_cloud(spawner: &mut PoolSpawner, position: Vec2, player: PlayerId) {
+    let sprite_to_use = rand::thread_rng().gen_range(0..CLOUD_SPRITE_TOTAL);
+    let sprite_min = Vec2::new(
+        (sprite_to_use % CLOUD_SPRITE_PER_ROW_COUNT) as f32 * CLOUD_SPRITE_SIZE.x,
+        (sprite_to_use / CLOUD_SPRITE_PER_ROW_COUNT) as f32 * CLOUD_SPRITE_SIZE.y,
+    );
+    let sprite_max = Vec2::new(
+        sprite_min.x + CLOUD_SPRITE_SIZE.x,
+        sprite_min.y + CLOUD_SPRITE_SIZE.y,
+    );
 
-    let mut rng = rand::thread_rng();
-
-    query.for_each(|(init_cloud, init_cloud_entity)| {
-        let pos = Vec3::new(init_cloud.0.x, init_cloud.0.y, 0.0);
-
-        let sprite_to_use = rng.gen_range(0..CLOUD_SPRITE_TOTAL);
-        let sprite_min = Vec2::new(
-            (sprite_to_use % CLOUD_SPRITE_PER_ROW_COUNT) as f32 * CLOUD_SPRITE_SIZE.x,
-            (sprite_to_use / CLOUD_SPRITE_PER_ROW_COUNT) as f32 * CLOUD_SPRITE_SIZE.y,
-        );
-        let sprite_max = Vec2::new(
-            sprite_min.x + CLOUD_SPRITE_SIZE.x,
-            sprite_min.y + CLOUD_SPRITE_SIZE.y,
-        );
-
-        commands.get_entity(init_cloud_entity).unwrap().despawn();
-        commands.spawn((
-            SpriteBundle {
-                texture: texture_assets.texture_clouds.clone(),
-                sprite: Sprite {
-                    rect: Some(Rect {
-                        min: sprite_min,
-                        max: sprite_max,
-                    }),
-                    ..Default::default()
-                },
-                transform: Transform::from_translation(pos),
+    let bundle = (
+        SpriteBundle {
+            texture: spawner.texture_assets.texture_clouds.clone(),
+            sprite: Sprite {
+                color: spawner.theme.colors.hazard,
+                rect: Some(Rect {
+                    min: sprite_min,
+                    max: sprite_max,
+                }),
                 ..Default::default()
             },
-            RelativeCoinY,
-            Cloud::default(),
-            StateOwner(AppState::Ingame),
-        ));
-    });
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..Default::default()
+        },
+        RelativeCoinY,
+        Cloud::default(),
+        player,
+        player.render_layers(),
+    );
+
+    let (entity, _) = match spawner.acquire(PoolKind::Cloud, player) {
+        Some(acquired) => acquired,
+        None => return,
+    };
+    spawner.commands.entity(entity).insert(bundle);
 }
 
 const CLOUD_SLOW_DOWN_PENALTY: f32 = 200.0;
+const NEAR_MISS_SPEED_NUDGE: f32 = 50.0;
 
 fn check_cloud_coin_collision(
-    mut coin_query: Query<(&mut Coin, &Transform)>,
-    mut cloud_query: Query<(&mut Cloud, &Transform, &mut Sprite), Without<Coin>>,
+    upgrades: Res<Upgrades>,
+    theme: Res<Theme>,
+    mut coin_query: Query<(&mut Coin, &Transform, &PlayerId)>,
+    mut cloud_query: Query<(&mut Cloud, &Transform, &mut Sprite, &PlayerId), Without<Coin>>,
+    mut cloud_hit_events: EventWriter<CloudHitEvent>,
+    mut near_miss_events: EventWriter<NearMissEvent>,
 ) {
-    let (mut coin, coin_transform) = coin_query.single_mut();
+    coin_query.for_each_mut(|(mut coin, coin_transform, coin_player)| {
+        if coin.speed < 0.0 {
+            return;
+        }
 
-    if coin.speed < 0.0 {
-        return;
-    }
+        let coin_rect = Coin::get_bounds(coin_transform);
 
-    let coin_rect = Coin::get_bounds(coin_transform);
+        cloud_query.for_each_mut(|(mut cloud, cloud_transform, mut cloud_sprite, player)| {
+            if !cloud.active || cloud.passed || player != coin_player {
+                return;
+            }
 
-    cloud_query.for_each_mut(|(mut cloud, cloud_transform, mut cloud_sprite)| {
-        if !cloud.active {
-            return;
-        }
+            let cloud_rect = Cloud::get_bounds(cloud_transform);
 
-        let cloud_rect = Cloud::get_bounds(cloud_transform);
+            if !cloud_rect.intersect(coin_rect).is_empty() {
+                cloud.active = false;
+                cloud_sprite.color = theme.colors.hazard_hit;
+                coin.speed -= CLOUD_SLOW_DOWN_PENALTY * upgrades.cloud_penalty_factor();
+                coin.speed = coin.speed.max(0.0);
+                cloud_hit_events.send(CloudHitEvent {
+                    player: *coin_player,
+                });
+            } else if !Cloud::get_near_miss_bounds(cloud_transform)
+                .intersect(coin_rect)
+                .is_empty()
+            {
+                cloud.grazed = true;
+            } else if cloud_rect.max.y < coin_rect.min.y {
+                cloud.passed = true;
 
-        if !cloud_rect.intersect(coin_rect).is_empty() {
-            cloud.active = false;
-            cloud_sprite.color = Color::GRAY;
-            coin.speed -= CLOUD_SLOW_DOWN_PENALTY;
-            coin.speed = coin.speed.max(0.0);
-        }
+                if cloud.grazed {
+                    coin.speed += NEAR_MISS_SPEED_NUDGE;
+                    near_miss_events.send(NearMissEvent {
+                        player: *coin_player,
+                    });
+                }
+            }
+        });
+    });
+}
+
+const NEAR_MISS_LABEL_SECONDS: f32 = 0.6;
+const NEAR_MISS_LABEL_OFFSET: Vec3 = Vec3::new(0.0, 48.0, 1.0);
+
+fn spawn_near_miss_labels(
+    mut commands: Commands,
+    mut near_miss_events: EventReader<NearMissEvent>,
+    coin_query: Query<(&PlayerId, Entity), With<Coin>>,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+) {
+    near_miss_events.iter().for_each(|event| {
+        let coin_entity = match for_player(
+            coin_query.iter().map(|(player, entity)| (entity, player)),
+            &event.player,
+        ) {
+            Some(coin_entity) => coin_entity,
+            None => return,
+        };
+
+        commands.entity(coin_entity).with_children(|parent| {
+            parent.spawn((
+                Text2dBundle {
+                    text: Text::from_section(
+                        strings.get("ingame.near_miss"),
+                        theme.text_style(&font_assets.font_fira, 28.0, theme.colors.accent),
+                    ),
+                    transform: Transform::from_translation(NEAR_MISS_LABEL_OFFSET),
+                    ..Default::default()
+                },
+                Tween::new(
+                    TweenTarget::Translation(
+                        NEAR_MISS_LABEL_OFFSET,
+                        NEAR_MISS_LABEL_OFFSET + Vec3::Y * 32.0,
+                    ),
+                    NEAR_MISS_LABEL_SECONDS,
+                    Ease::QuadOut,
+                )
+                .and(TweenTarget::TextColor(
+                    theme.colors.accent,
+                    theme.colors.accent.with_a(0.0),
+                ))
+                .and(TweenTarget::Rotation(
+                    Quat::from_rotation_z(-0.2),
+                    Quat::IDENTITY,
+                )),
+                DespawnOnTweenCompleted,
+                event.player.render_layers(),
+            ));
+        });
+    });
+}
+
+const INVISIBLE_CLOUD_REVEAL_DISTANCE: f32 = 150.0;
+const INVISIBLE_CLOUD_FADE_DISTANCE: f32 = 100.0;
+
+fn reveal_nearby_clouds(
+    modifiers: Res<ActiveModifiers>,
+    coin_query: Query<(&Transform, &PlayerId), With<Coin>>,
+    mut cloud_query: Query<(&Transform, &mut Sprite, &PlayerId), With<Cloud>>,
+) {
+    if !modifiers.has(Modifier::InvisibleClouds) {
+        return;
+    }
+
+    cloud_query.for_each_mut(|(cloud_transform, mut cloud_sprite, player)| {
+        let coin_pos = match for_player(coin_query.iter(), player) {
+            Some(coin_transform) => coin_transform.translation.truncate(),
+            None => return,
+        };
+        let distance = cloud_transform.translation.truncate().distance(coin_pos);
+        let alpha = 1.0
+            - ((distance - INVISIBLE_CLOUD_REVEAL_DISTANCE) / INVISIBLE_CLOUD_FADE_DISTANCE)
+                .clamp(0.0, 1.0);
+        cloud_sprite.color.set_a(alpha);
     });
 }
@@ -1,28 +1,56 @@
 use bevy::prelude::*;
 
 use crate::{
-    app_state::{AppState, StateOwner},
+    anim::FrameAnimation,
+    app_state::{in_flight, AppState, FlightPhase, StateOwner},
+    cloud::CloudHitEvent,
     coin_camera::COIN_SCREEN_BOUNDS_X,
     coin_launch_ui::CoinLaunchSpeedPercentage,
+    cosmetics::{SelectedCosmetics, TrailEmitter},
     game_assets::TextureAssets,
-    physics::RelativeCoinY,
+    game_mode::{DailyChallenge, GameMode},
+    modifiers::{ActiveModifiers, Modifier},
+    physics::{RelativeCoinY, TimeScale},
+    player::{PlayerControls, PlayerId},
+    rescue::RescueChance,
     scores::Scores,
+    scoring::RunScore,
+    theme::Theme,
+    transition::not_leaving_state,
+    upgrades::Upgrades,
 };
 
 pub struct CoinPlugin;
 
 impl Plugin for CoinPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Ingame), (setup_coin, setup_launcher))
+        app.add_event::<ManualBoostUsedEvent>()
+            .add_event::<GameOverEvent>()
+            .insert_resource(GravityBurstClock::default())
+            .add_systems(
+                OnEnter(AppState::Ingame),
+                (setup_coin, setup_launcher, reset_gravity_burst_clock),
+            )
             .add_systems(
                 Update,
                 (
-                    handle_coin_gravity,
+                    (
+                        handle_coin_gravity,
+                        handle_coin_adjustments,
+                        handle_coin_use_boost,
+                        hold_out_coins,
+                    )
+                        .run_if(in_flight),
                     do_coin_flip_animation,
-                    handle_coin_adjustments,
-                    handle_coin_use_boost,
                     calculate_altitude,
-                    check_game_over,
+                    (
+                        (stop_out_coins, check_game_over)
+                            .chain()
+                            .run_if(in_state(FlightPhase::Flying)),
+                        end_flight,
+                    )
+                        .chain()
+                        .run_if(not_leaving_state),
                 )
                     .run_if(in_state(AppState::Ingame)),
             );
@@ -41,13 +69,18 @@ impl Default for Coin {
     fn default() -> Self {
         Self {
             speed: 0.0,
-            additional_boosts: 3,
+            additional_boosts: COIN_START_BOOSTS,
             altitude: 0.0,
             highest_altitude_recorded: 0.0,
         }
     }
 }
 
+/// A coin that is out of the flight, while a race may go on for the other player. It stays where
+/// it fell.
+#[derive(Component)]
+pub struct OutOfRace;
+
 impl Coin {
     pub fn get_bounds(transform: &Transform) -> Rect {
         Rect::from_center_size(
@@ -55,171 +88,320 @@ impl Coin {
             COIN_FULL_SIZE,
         )
     }
-}
 
-#[derive(Component)]
-pub struct CoinAnimation {
-    orientation: f32,
-    direction: f32,
+    /// Falling too fast to recover, unless a rescue gets it going again.
+    pub fn is_out(&self) -> bool {
+        self.speed < COIN_LOSE_SPEED
+    }
 }
 
-impl Default for CoinAnimation {
-    fn default() -> Self {
-        Self {
-            orientation: 1.0,
-            direction: -1.0,
-        }
-    }
+#[derive(Event)]
+pub struct ManualBoostUsedEvent {
+    pub player: PlayerId,
+    pub speed_before: f32,
 }
 
+/// The flight is over, the scores are registered and the coins fall back.
+#[derive(Event)]
+pub struct GameOverEvent;
+
+/// Where the coins are launched from, and land again after the flight.
+#[derive(Component)]
+pub struct Launcher;
+
 const GRAVITY: f32 = 98.0;
-const COIN_FULL_SIZE: Vec2 = Vec2::new(50.0, 50.0);
+const LOW_GRAVITY_FACTOR: f32 = 0.6;
+const COIN_START_BOOSTS: i32 = 3;
+pub const COIN_FULL_SIZE: Vec2 = Vec2::new(50.0, 50.0);
 const COIN_MIN_START_SPEED: f32 = 400.0;
 const COIN_MAX_START_SPEED: f32 = 1400.0;
 
 fn setup_coin(
     mut commands: Commands,
     launch_speed_percentage: Res<CoinLaunchSpeedPercentage>,
+    modifiers: Res<ActiveModifiers>,
+    upgrades: Res<Upgrades>,
+    cosmetics: Res<SelectedCosmetics>,
+    game_mode: Res<GameMode>,
     texture_assets: Res<TextureAssets>,
 ) {
-    commands.spawn((
-        SpriteBundle {
-            texture: texture_assets.texture_coin.clone(),
-            ..Default::default()
-        },
-        Coin {
-            speed: COIN_MIN_START_SPEED
-                + (COIN_MAX_START_SPEED - COIN_MIN_START_SPEED) * launch_speed_percentage.0,
-            additional_boosts: 3,
-            altitude: 0.0,
-            highest_altitude_recorded: 0.0,
-        },
-        CoinAnimation::default(),
-        StateOwner(AppState::Ingame),
-    ));
+    let player_count = game_mode.player_count();
+
+    PlayerId::all(player_count).for_each(|player| {
+        commands.spawn((
+            SpriteSheetBundle {
+                texture_atlas: cosmetics.skin.spin_atlas(&texture_assets),
+                sprite: TextureAtlasSprite {
+                    custom_size: Some(COIN_FULL_SIZE),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Coin {
+                speed: COIN_MIN_START_SPEED
+                    + (COIN_MAX_START_SPEED - COIN_MIN_START_SPEED) * launch_speed_percentage.0,
+                additional_boosts: if modifiers.has(Modifier::NoManualBoosts) {
+                    0
+                } else {
+                    COIN_START_BOOSTS + upgrades.extra_boosts()
+                },
+                altitude: 0.0,
+                highest_altitude_recorded: 0.0,
+            },
+            FrameAnimation::new(COIN_SPIN_FRAMES, 0.0),
+            TrailEmitter::new(cosmetics.trail),
+            RunScore::default(),
+            player,
+            PlayerControls::for_player(player, player_count),
+            player.render_layers(),
+            StateOwner(AppState::Ingame),
+        ));
+    });
+}
+
+fn setup_launcher(
+    mut commands: Commands,
+    game_mode: Res<GameMode>,
+    texture_assets: Res<TextureAssets>,
+) {
+    PlayerId::all(game_mode.player_count()).for_each(|player| {
+        commands.spawn((
+            SpriteBundle {
+                texture: texture_assets.texture_launcher.clone(),
+                transform: Transform::from_translation(Vec3::new(0.0, -240.0, 2.0)),
+                ..Default::default()
+            },
+            RelativeCoinY,
+            Launcher,
+            player,
+            player.render_layers(),
+            StateOwner(AppState::Ingame),
+        ));
+    });
 }
 
-fn setup_launcher(mut commands: Commands, texture_assets: Res<TextureAssets>) {
-    commands.spawn((
-        SpriteBundle {
-            texture: texture_assets.texture_launcher.clone(),
-            transform: Transform::from_translation(Vec3::new(0.0, -240.0, 2.0)),
-            ..Default::default()
-        },
-        RelativeCoinY,
-        StateOwner(AppState::Ingame),
-    ));
+/// Time spent in the current run, drives the reverse gravity bursts.
+#[derive(Resource, Default)]
+struct GravityBurstClock(f32);
+
+const GRAVITY_BURST_INTERVAL: f32 = 6.0;
+const GRAVITY_BURST_DURATION: f32 = 1.0;
+
+fn reset_gravity_burst_clock(mut clock: ResMut<GravityBurstClock>) {
+    clock.0 = 0.0;
 }
 
-fn handle_coin_gravity(time: Res<Time>, mut query: Query<&mut Coin>) {
+fn handle_coin_gravity(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    modifiers: Res<ActiveModifiers>,
+    mut clock: ResMut<GravityBurstClock>,
+    mut query: Query<&mut Coin, Without<OutOfRace>>,
+) {
+    let delta_seconds = time_scale.delta_seconds(&time);
+    let mut gravity = if modifiers.has(Modifier::LowGravity) {
+        GRAVITY * LOW_GRAVITY_FACTOR
+    } else {
+        GRAVITY
+    };
+
+    if modifiers.has(Modifier::ReverseGravityBursts) {
+        clock.0 += delta_seconds;
+        if clock.0 % GRAVITY_BURST_INTERVAL > GRAVITY_BURST_INTERVAL - GRAVITY_BURST_DURATION {
+            gravity = -gravity;
+        }
+    }
+
     query.for_each_mut(|mut coin| {
-        coin.speed += -GRAVITY * time.delta_seconds();
+        coin.speed += -gravity * delta_seconds;
     });
 }
 
 const COIN_ANIM_MAX_COIN_SPEED_CAP: f32 = 2400.0;
 const COIN_ANIM_MIN_COIN_SPEED_CAP: f32 = 100.0;
-const COIN_ANIM_MAX_SPIN_SPEED: f32 = 15.0;
+/// Spin frames per second at full speed.
+const COIN_ANIM_MAX_SPIN_SPEED: f32 = 30.0;
+/// Frames in a full turn of the coin spin atlas: face, edge, tails, edge.
+pub const COIN_SPIN_FRAMES: usize = 8;
 
-fn do_coin_flip_animation(
-    time: Res<Time>,
-    mut query: Query<(&mut CoinAnimation, &mut Sprite, &Coin)>,
-) {
-    query.for_each_mut(|(mut anim, mut sprite, coin)| {
-        if coin.speed.abs() > 0.01 {
-            let spin_speed = COIN_ANIM_MAX_SPIN_SPEED
+fn do_coin_flip_animation(theme: Res<Theme>, mut query: Query<(&mut FrameAnimation, &Coin)>) {
+    query.for_each_mut(|(mut anim, coin)| {
+        anim.fps = if !theme.reduced_motion && coin.speed.abs() > 0.01 {
+            COIN_ANIM_MAX_SPIN_SPEED
                 * (coin
                     .speed
                     .abs()
-                    .min(COIN_ANIM_MAX_COIN_SPEED_CAP)
-                    .max(COIN_ANIM_MIN_COIN_SPEED_CAP)
+                    .clamp(COIN_ANIM_MIN_COIN_SPEED_CAP, COIN_ANIM_MAX_COIN_SPEED_CAP)
                     / COIN_ANIM_MAX_COIN_SPEED_CAP)
-                    .powf(0.5);
-            anim.orientation += anim.direction * spin_speed * time.delta_seconds();
-
-            if anim.direction < 0.0 {
-                if anim.orientation < -1.0 {
-                    anim.orientation += -1.0 - anim.orientation;
-                    anim.direction = 1.0;
-                }
-            } else {
-                if anim.orientation > 1.0 {
-                    anim.orientation -= anim.orientation - 1.0;
-                    anim.direction = -1.0;
-                }
-            }
-
-            sprite.custom_size = Some(Vec2::new(
-                COIN_FULL_SIZE.x,
-                COIN_FULL_SIZE.y * anim.orientation,
-            ));
-        }
+                    .powf(0.5)
+        } else {
+            0.0
+        };
     });
 }
 
-const COIN_ADJUSTMENT_X_SPEED: f32 = 200.0;
+pub const COIN_ADJUSTMENT_X_SPEED: f32 = 200.0;
 const COIN_ADJUSTMENT_Y_SPEED_PENALTY: f32 = 90.0;
 
 fn handle_coin_adjustments(
     time: Res<Time>,
+    time_scale: Res<TimeScale>,
     keyboard: Res<Input<KeyCode>>,
-    mut query: Query<(&mut Transform, &mut Coin)>,
+    modifiers: Res<ActiveModifiers>,
+    mut query: Query<(&mut Transform, &mut Coin, &PlayerControls), Without<OutOfRace>>,
 ) {
-    let left_pressed = keyboard.pressed(KeyCode::Left);
-    let right_pressed = keyboard.pressed(KeyCode::Right);
+    let delta_seconds = time_scale.delta_seconds(&time);
+    query.for_each_mut(|(mut transform, mut coin, controls)| {
+        let left_pressed = keyboard.pressed(controls.left);
+        let right_pressed = keyboard.pressed(controls.right);
 
-    if left_pressed == right_pressed {
-        return;
-    }
+        if left_pressed == right_pressed {
+            return;
+        }
 
-    let direction = if left_pressed { -1.0 } else { 1.0 };
+        let mut direction = if left_pressed { -1.0 } else { 1.0 };
+        if modifiers.has(Modifier::MirrorControls) {
+            direction = -direction;
+        }
 
-    query.for_each_mut(|(mut transform, mut coin)| {
-        transform.translation.x += direction * COIN_ADJUSTMENT_X_SPEED * time.delta_seconds();
+        transform.translation.x += direction * COIN_ADJUSTMENT_X_SPEED * delta_seconds;
         transform.translation.x = transform
             .translation
             .x
-            .max(-COIN_SCREEN_BOUNDS_X)
-            .min(COIN_SCREEN_BOUNDS_X);
-        coin.speed -= COIN_ADJUSTMENT_Y_SPEED_PENALTY * time.delta_seconds();
+            .clamp(-COIN_SCREEN_BOUNDS_X, COIN_SCREEN_BOUNDS_X);
+        coin.speed -= COIN_ADJUSTMENT_Y_SPEED_PENALTY * delta_seconds;
     });
 }
 
 const COIN_MANUAL_BOOST_SPEED_GAIN: f32 = 200.0;
 
-fn handle_coin_use_boost(keyboard: Res<Input<KeyCode>>, mut query: Query<&mut Coin>) {
-    if !keyboard.just_pressed(KeyCode::Space) {
+fn handle_coin_use_boost(
+    keyboard: Res<Input<KeyCode>>,
+    modifiers: Res<ActiveModifiers>,
+    upgrades: Res<Upgrades>,
+    mut query: Query<(&mut Coin, &PlayerControls, &PlayerId), Without<OutOfRace>>,
+    mut boost_used_events: EventWriter<ManualBoostUsedEvent>,
+) {
+    if modifiers.has(Modifier::NoManualBoosts) {
         return;
     }
 
-    query.for_each_mut(|mut coin| {
-        if coin.additional_boosts <= 0 {
+    query.for_each_mut(|(mut coin, controls, player)| {
+        if !keyboard.just_pressed(controls.boost) || coin.additional_boosts <= 0 {
             return;
         }
 
+        boost_used_events.send(ManualBoostUsedEvent {
+            player: *player,
+            speed_before: coin.speed,
+        });
         coin.additional_boosts -= 1;
-        coin.speed += COIN_MANUAL_BOOST_SPEED_GAIN;
+        coin.speed += COIN_MANUAL_BOOST_SPEED_GAIN + upgrades.boost_power_bonus();
     });
 }
 
-fn calculate_altitude(time: Res<Time>, mut query: Query<&mut Coin>) {
+fn calculate_altitude(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut query: Query<&mut Coin, Without<OutOfRace>>,
+) {
+    let delta_seconds = time_scale.delta_seconds(&time);
     query.for_each_mut(|mut coin| {
-        coin.altitude += coin.speed * time.delta_seconds();
+        coin.altitude += coin.speed * delta_seconds;
         coin.highest_altitude_recorded = coin.highest_altitude_recorded.max(coin.altitude);
     });
 }
 
-const COIN_LOSE_SPEED: f32 = -400.0;
+pub const COIN_LOSE_SPEED: f32 = -400.0;
+
+/// Takes the coins that crashed, or fell too fast with no rescue left, out of the flight. The
+/// others fly on, or get a last chance if they are falling.
+fn stop_out_coins(
+    mut commands: Commands,
+    modifiers: Res<ActiveModifiers>,
+    rescue_chance: RescueChance,
+    mut cloud_hit_events: EventReader<CloudHitEvent>,
+    mut next_phase: ResMut<NextState<FlightPhase>>,
+    query: Query<(&Coin, &PlayerId, Entity), Without<OutOfRace>>,
+) {
+    let crashed_players = if modifiers.has(Modifier::OneHitGameOver) {
+        cloud_hit_events.iter().map(|event| event.player).collect()
+    } else {
+        cloud_hit_events.clear();
+        vec![]
+    };
+
+    let can_rescue = rescue_chance.exists();
+    query.for_each(|(coin, player, entity)| {
+        if crashed_players.contains(player) || (coin.is_out() && !can_rescue) {
+            commands.entity(entity).insert(OutOfRace);
+        } else if coin.is_out() {
+            next_phase.set(FlightPhase::Rescue);
+        }
+    });
+}
+
+/// Nothing gets a coin that is out going again, e.g. a fairy flying into it.
+fn hold_out_coins(mut query: Query<&mut Coin, With<OutOfRace>>) {
+    query.for_each_mut(|mut coin| {
+        coin.speed = 0.0;
+    });
+}
 
+/// The flight ends once every coin is out; in a race, the highest flight wins.
 fn check_game_over(
-    query: Query<&Coin>,
-    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<(), With<Coin>>,
+    out_query: Query<(), With<OutOfRace>>,
+    mut game_over_events: EventWriter<GameOverEvent>,
+) {
+    if out_query.iter().count() == query.iter().count() {
+        game_over_events.send(GameOverEvent);
+    }
+}
+
+fn end_flight(
+    query: Query<(&Coin, &RunScore, &PlayerId)>,
+    game_mode: Res<GameMode>,
+    daily: Res<DailyChallenge>,
+    modifiers: Res<ActiveModifiers>,
+    mut game_over_events: EventReader<GameOverEvent>,
+    mut next_phase: ResMut<NextState<FlightPhase>>,
     mut scores: ResMut<Scores>,
 ) {
-    let coin = query.single();
+    if game_over_events.iter().count() == 0 {
+        return;
+    }
+
+    let multiplier = modifiers.score_multiplier();
+    let mut player_scores = query
+        .iter()
+        .map(|(coin, run_score, player)| {
+            let total = run_score.total(coin.highest_altitude_recorded);
+            (*player, (total * multiplier).round() as i32)
+        })
+        .collect::<Vec<_>>();
+    player_scores.sort_by_key(|(player, _)| player.0);
+    let score = player_scores
+        .iter()
+        .map(|(_, score)| *score)
+        .max()
+        .unwrap_or_default();
 
-    if coin.speed < COIN_LOSE_SPEED {
-        scores.register_score((coin.highest_altitude_recorded.ceil() as i32) / 10);
-        next_state.set(AppState::End);
+    let altitude = query
+        .iter()
+        .map(|(coin, _, _)| (coin.highest_altitude_recorded.ceil() as i32) / 10)
+        .max()
+        .unwrap_or_default();
+
+    scores.end_multiplier = multiplier;
+    scores.register_altitude(altitude, *game_mode == GameMode::Endless);
+    match *game_mode {
+        GameMode::Endless => scores.register_score(score),
+        GameMode::Daily => scores.register_daily_score(score, daily.day, daily.scored),
+        GameMode::TimeAttack(_) | GameMode::Tutorial => scores.register_unranked_score(score),
+        GameMode::Race => {
+            scores.register_race_scores(player_scores.into_iter().map(|(_, score)| score).collect())
+        }
     }
+    next_phase.set(FlightPhase::Landing);
 }
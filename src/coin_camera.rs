@@ -1,10 +1,15 @@
-use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*, render::view::RenderLayers};
 
 use crate::{
-    app_state::{AppState, StateOwner},
+    app_state::{in_flight, AppState, StateOwner},
     coin::Coin,
     coin_launch_ui::SKY_COLOR,
+    display::PlayfieldViewport,
+    game_mode::GameMode,
     math::lerp,
+    physics::TimeScale,
+    player::{for_player, PlayerId},
+    theme::Theme,
 };
 
 pub struct CoinCameraPlugin;
@@ -14,18 +19,73 @@ impl Plugin for CoinCameraPlugin {
         app.add_systems(OnEnter(AppState::Ingame), setup_coin_camera)
             .add_systems(
                 Update,
-                (pan_camera_with_coin_speed, set_sky_color).run_if(in_state(AppState::Ingame)),
+                (pan_camera_with_coin_speed.run_if(in_flight), set_sky_color)
+                    .run_if(in_state(AppState::Ingame)),
             );
     }
 }
 
 #[derive(Component)]
-struct CoinCamera;
+pub struct CoinCamera;
+
+/// Each player gets a camera that only sees that player's world, the HUD is
+/// drawn over all of them by a separate camera.
+fn setup_coin_camera(mut commands: Commands, game_mode: Res<GameMode>) {
+    let player_count = game_mode.player_count();
+
+    // clears the bars around the playfield, when the window has another aspect
+    commands.spawn((
+        Camera2dBundle {
+            camera: Camera {
+                order: -1,
+                ..Default::default()
+            },
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::Custom(Color::BLACK),
+            },
+            ..Default::default()
+        },
+        UiCameraConfig { show_ui: false },
+        RenderLayers::none(),
+        StateOwner(AppState::Ingame),
+    ));
+
+    PlayerId::all(player_count).for_each(|player| {
+        let viewport = PlayfieldViewport {
+            index: player.0 as u32,
+            count: player_count as u32,
+        };
+
+        commands.spawn((
+            Camera2dBundle {
+                camera: Camera {
+                    order: player.0 as isize,
+                    ..Default::default()
+                },
+                projection: viewport.projection(),
+                ..Default::default()
+            },
+            UiCameraConfig { show_ui: false },
+            viewport,
+            CoinCamera,
+            player,
+            player.render_layers(),
+            StateOwner(AppState::Ingame),
+        ));
+    });
 
-fn setup_coin_camera(mut commands: Commands) {
     commands.spawn((
-        Camera2dBundle::default(),
-        CoinCamera,
+        Camera2dBundle {
+            camera: Camera {
+                order: player_count as isize,
+                ..Default::default()
+            },
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::None,
+            },
+            ..Default::default()
+        },
+        RenderLayers::none(),
         StateOwner(AppState::Ingame),
     ));
 }
@@ -37,33 +97,56 @@ const CAMERA_PAN_Y_DIST: f32 = COIN_SCREEN_BOUNDS_Y - 200.0;
 
 fn pan_camera_with_coin_speed(
     time: Res<Time>,
-    coin_query: Query<&Coin>,
-    mut camera_query: Query<&mut Transform, With<CoinCamera>>,
+    time_scale: Res<TimeScale>,
+    coin_query: Query<(&Coin, &PlayerId)>,
+    mut camera_query: Query<(&mut Transform, &PlayerId), With<CoinCamera>>,
 ) {
-    let coin = coin_query.single();
-    let mut camera_transform = camera_query.single_mut();
+    camera_query.for_each_mut(|(mut camera_transform, camera_player)| {
+        let coin = match for_player(coin_query.iter(), camera_player) {
+            Some(coin) => coin,
+            None => return,
+        };
 
-    let target_y = CAMERA_PAN_Y_DIST - coin.speed.min(0.0);
+        let target_y = CAMERA_PAN_Y_DIST - coin.speed.min(0.0);
 
-    let dist = target_y - camera_transform.translation.y;
+        let dist = target_y - camera_transform.translation.y;
 
-    if dist.abs() < 0.01 {
-        return;
-    }
+        if dist.abs() < 0.01 {
+            return;
+        }
 
-    camera_transform.translation.y += dist * time.delta_seconds();
+        camera_transform.translation.y += dist * time_scale.delta_seconds(&time);
+    });
 }
 
+/// How fast the sky may darken or brighten with reduced motion, in fade per second.
+const REDUCED_MOTION_SKY_FADE_SPEED: f32 = 0.1;
+
 fn set_sky_color(
-    coin_query: Query<&Coin>,
-    mut camera_query: Query<&mut Camera2d, With<CoinCamera>>,
+    time: Res<Time>,
+    theme: Res<Theme>,
+    coin_query: Query<(&Coin, &PlayerId)>,
+    mut camera_query: Query<(&mut Camera2d, &PlayerId), With<CoinCamera>>,
 ) {
-    let coin = coin_query.single();
-    let mut camera = camera_query.single_mut();
-    let color_fade = (coin.altitude / 20000.0).min(1.0);
-    camera.clear_color = ClearColorConfig::Custom(Color::rgb(
-        lerp(SKY_COLOR.r(), 0.0, color_fade),
-        lerp(SKY_COLOR.g(), 0.0, color_fade),
-        lerp(SKY_COLOR.b(), 0.0, color_fade),
-    ));
+    camera_query.for_each_mut(|(mut camera, camera_player)| {
+        let coin = match for_player(coin_query.iter(), camera_player) {
+            Some(coin) => coin,
+            None => return,
+        };
+
+        let mut color_fade = (coin.altitude / 20000.0).min(1.0);
+        if theme.reduced_motion {
+            // boosts and falls would flash the sky, so it only creeps towards the altitude's colour
+            if let ClearColorConfig::Custom(current) = camera.clear_color {
+                let current_fade = 1.0 - current.r() / SKY_COLOR.r();
+                let max_step = REDUCED_MOTION_SKY_FADE_SPEED * time.delta_seconds();
+                color_fade = current_fade + (color_fade - current_fade).clamp(-max_step, max_step);
+            }
+        }
+        camera.clear_color = ClearColorConfig::Custom(Color::rgb(
+            lerp(SKY_COLOR.r(), 0.0, color_fade),
+            lerp(SKY_COLOR.g(), 0.0, color_fade),
+            lerp(SKY_COLOR.b(), 0.0, color_fade),
+        ));
+    });
 }
@@ -1,8 +1,19 @@
-use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+use bevy::{core_pipeline::clear_color::ClearColorConfig, ecs::system::SystemParam, prelude::*};
 
 use crate::{
+    anim::{Ease, Tween, TweenMode, TweenTarget},
     app_state::{AppState, StateOwner},
+    display::{fit_projection, PlayfieldUi},
     game_assets::{FontAssets, TextureAssets},
+    game_mode::{today, DailyChallenge, GameMode},
+    locale::Strings,
+    modifiers::{Modifier, SelectedModifiers},
+    player::{PlayerControls, PlayerId},
+    scores::Scores,
+    theme::Theme,
+    time_attack::TIME_ATTACK_GOALS,
+    transition::{not_leaving_state, ScreenTransition},
+    upgrades::Upgrades,
 };
 
 pub struct CoinLaunchUiPlugin;
@@ -19,7 +30,17 @@ impl Plugin for CoinLaunchUiPlugin {
             )
             .add_systems(
                 Update,
-                (update_speed, update_speed_ui, launch_coin).run_if(in_state(AppState::CoinLaunch)),
+                (
+                    update_speed,
+                    update_speed_ui,
+                    launch_coin.after(update_speed),
+                    toggle_modifiers,
+                    update_modifiers_ui,
+                    select_time_attack_goal,
+                    update_time_attack_goals_ui,
+                )
+                    .run_if(in_state(AppState::CoinLaunch))
+                    .run_if(not_leaving_state),
             );
     }
 }
@@ -31,82 +52,152 @@ fn reset_launch_speed(mut speed: ResMut<CoinLaunchSpeedPercentage>) {
 #[derive(Component)]
 struct SpeedIndicatorUi;
 
+#[derive(Component)]
+struct ModifierToggleUi(Modifier);
+
+#[derive(Component)]
+struct ModifierMultiplierUi;
+
+#[derive(Component)]
+struct TimeAttackGoalUi(u32);
+
+/// Number keys used to pick the options of the current game mode.
+const OPTION_KEYS: [KeyCode; 7] = [
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+];
+
 const SPEED_BAR_HEIGHT: f32 = 200.0;
 const SPEED_BAR_TOP: f32 = 32.0;
 
 const SPEED_INDICATOR_HEIGHT: f32 = 16.0;
 
+/// Opacity of the full power zone over the speed bar.
+const SWEET_SPOT_ALPHA: f32 = 0.4;
+
 pub const SKY_COLOR: Color = Color::rgb(145.0 / 255.0, 142.0 / 255.0, 229.0 / 255.0);
 
+/// What the upcoming flight looks like, shown before the launch.
+#[derive(SystemParam)]
+struct RunSetup<'w> {
+    game_mode: Res<'w, GameMode>,
+    scores: Res<'w, Scores>,
+    selected_modifiers: Res<'w, SelectedModifiers>,
+    upgrades: Res<'w, Upgrades>,
+}
+
 fn setup_coin_launch_ui(
     mut commands: Commands,
     texture_assets: Res<TextureAssets>,
     font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    run: RunSetup,
 ) {
+    let RunSetup {
+        game_mode,
+        scores,
+        selected_modifiers,
+        upgrades,
+    } = run;
+
     commands.spawn((
         Camera2dBundle {
             camera_2d: Camera2d {
                 clear_color: ClearColorConfig::Custom(SKY_COLOR),
-                ..Default::default()
             },
+            projection: fit_projection(),
             ..Default::default()
         },
         StateOwner(AppState::CoinLaunch),
     ));
 
-    commands.spawn((
-        NodeBundle {
+    // the launcher sprite is placed in the playfield, so its UI is too
+    let playfield = commands
+        .spawn((
+            NodeBundle::default(),
+            PlayfieldUi,
+            StateOwner(AppState::CoinLaunch),
+        ))
+        .id();
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(30.0),
+                    height: Val::Px(SPEED_BAR_HEIGHT),
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(SPEED_BAR_TOP),
+                    right: Val::Px(100.0),
+                    ..Default::default()
+                },
+                background_color: Color::WHITE.into(),
+                ..Default::default()
+            },
+            UiImage::new(texture_assets.texture_launch_bar.clone()),
+        ))
+        .set_parent(playfield);
+
+    commands
+        .spawn(NodeBundle {
             style: Style {
                 width: Val::Px(30.0),
-                height: Val::Px(SPEED_BAR_HEIGHT),
+                height: Val::Px((1.0 - upgrades.launch_sweet_spot()) * SPEED_BAR_HEIGHT),
                 position_type: PositionType::Absolute,
                 top: Val::Px(SPEED_BAR_TOP),
                 right: Val::Px(100.0),
                 ..Default::default()
             },
-            background_color: Color::WHITE.into(),
+            background_color: theme.colors.positive.with_a(SWEET_SPOT_ALPHA).into(),
             ..Default::default()
-        },
-        UiImage::new(texture_assets.texture_launch_bar.clone()),
-        StateOwner(AppState::CoinLaunch),
-    ));
+        })
+        .set_parent(playfield);
 
-    commands.spawn((
-        NodeBundle {
-            style: Style {
-                width: Val::Px(16.0),
-                height: Val::Px(SPEED_INDICATOR_HEIGHT),
-                position_type: PositionType::Absolute,
-                top: Val::Px(SPEED_BAR_TOP + SPEED_BAR_HEIGHT - SPEED_INDICATOR_HEIGHT * 0.5),
-                right: Val::Px(80.0),
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Px(16.0),
+                    height: Val::Px(SPEED_INDICATOR_HEIGHT),
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(SPEED_BAR_TOP + SPEED_BAR_HEIGHT - SPEED_INDICATOR_HEIGHT * 0.5),
+                    right: Val::Px(80.0),
+                    ..Default::default()
+                },
+                background_color: Color::WHITE.into(),
                 ..Default::default()
             },
-            background_color: Color::WHITE.into(),
-            ..Default::default()
-        },
-        SpeedIndicatorUi,
-        UiImage::new(texture_assets.texture_launch_arrow.clone()),
-        StateOwner(AppState::CoinLaunch),
-    ));
+            SpeedIndicatorUi,
+            UiImage::new(texture_assets.texture_launch_arrow.clone()),
+            Tween::new(
+                TweenTarget::BackgroundColor(Color::WHITE, theme.colors.accent),
+                0.3,
+                Ease::QuadInOut,
+            )
+            .with_mode(TweenMode::PingPong),
+        ))
+        .set_parent(playfield);
 
-    commands.spawn((
-        TextBundle::from_section(
-            "[SPACE]: GO!",
-            TextStyle {
-                font: font_assets.font_fira.clone(),
-                font_size: 32.0,
-                color: Color::GREEN,
+    commands
+        .spawn(
+            TextBundle::from_section(
+                strings.get("launch.go"),
+                theme.text_style(&font_assets.font_fira, 32.0, theme.colors.positive),
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(SPEED_BAR_TOP + SPEED_BAR_HEIGHT + 16.0),
+                right: Val::Px(32.0),
                 ..Default::default()
-            },
+            }),
         )
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: Val::Px(SPEED_BAR_TOP + SPEED_BAR_HEIGHT + 16.0),
-            right: Val::Px(32.0),
-            ..Default::default()
-        }),
-        StateOwner(AppState::CoinLaunch),
-    ));
+        .set_parent(playfield);
 
     commands.spawn((
         SpriteBundle {
@@ -116,6 +207,260 @@ fn setup_coin_launch_ui(
         },
         StateOwner(AppState::CoinLaunch),
     ));
+
+    match *game_mode {
+        GameMode::Daily => {
+            let day = today();
+
+            commands
+                .spawn(
+                    TextBundle::from_sections([
+                        TextSection::new(
+                            format!(
+                                "{}\n",
+                                strings.get(if scores.has_attempted_daily(day) {
+                                    "launch.daily_practice"
+                                } else {
+                                    "launch.daily"
+                                })
+                            ),
+                            theme.text_style(&font_assets.font_fira, 32.0, theme.colors.accent),
+                        ),
+                        TextSection::new(
+                            DailyChallenge::modifiers(day).describe(&strings),
+                            theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                        ),
+                    ])
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(SPEED_BAR_TOP),
+                        left: Val::Px(32.0),
+                        ..Default::default()
+                    }),
+                )
+                .set_parent(playfield);
+        }
+        GameMode::Endless => {
+            commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(SPEED_BAR_TOP),
+                        left: Val::Px(32.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .set_parent(playfield)
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        strings.get("launch.modifiers"),
+                        theme.text_style(&font_assets.font_fira, 32.0, theme.colors.accent),
+                    ));
+
+                    Modifier::ALL
+                        .iter()
+                        .enumerate()
+                        .for_each(|(index, modifier)| {
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    strings.format(
+                                        "launch.modifier_option",
+                                        &[
+                                            ("key", &(index + 1)),
+                                            ("modifier", &modifier.name(&strings)),
+                                            (
+                                                "multiplier",
+                                                &format!("{:.1}", modifier.score_multiplier()),
+                                            ),
+                                        ],
+                                    ),
+                                    theme.text_style(
+                                        &font_assets.font_fira,
+                                        20.0,
+                                        option_color(&theme, selected_modifiers.0.has(*modifier)),
+                                    ),
+                                ),
+                                ModifierToggleUi(*modifier),
+                            ));
+                        });
+
+                    parent.spawn((
+                        TextBundle::from_section(
+                            score_multiplier_text(&strings, &selected_modifiers),
+                            theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                        ),
+                        ModifierMultiplierUi,
+                    ));
+                });
+        }
+        GameMode::TimeAttack(selected_goal) => {
+            commands
+                .spawn(NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(SPEED_BAR_TOP),
+                        left: Val::Px(32.0),
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .set_parent(playfield)
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        strings.get("launch.time_attack"),
+                        theme.text_style(&font_assets.font_fira, 32.0, theme.colors.accent),
+                    ));
+
+                    TIME_ATTACK_GOALS
+                        .iter()
+                        .enumerate()
+                        .for_each(|(index, goal)| {
+                            let best = match scores.best_times.get(goal) {
+                                Some(record) => strings.format(
+                                    "launch.goal_best",
+                                    &[("time", &format!("{:.2}", record.time))],
+                                ),
+                                None => strings.get("launch.goal_no_best").to_string(),
+                            };
+
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    strings.format(
+                                        "launch.goal_option",
+                                        &[("key", &(index + 1)), ("goal", goal), ("best", &best)],
+                                    ),
+                                    theme.text_style(
+                                        &font_assets.font_fira,
+                                        20.0,
+                                        option_color(&theme, *goal == selected_goal),
+                                    ),
+                                ),
+                                TimeAttackGoalUi(*goal),
+                            ));
+                        });
+                });
+        }
+        GameMode::Tutorial => {
+            commands
+                .spawn(
+                    TextBundle::from_sections([
+                        TextSection::new(
+                            format!("{}\n", strings.get("launch.tutorial")),
+                            theme.text_style(&font_assets.font_fira, 32.0, theme.colors.accent),
+                        ),
+                        TextSection::new(
+                            strings.get("launch.tutorial_hint"),
+                            theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                        ),
+                    ])
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(SPEED_BAR_TOP),
+                        left: Val::Px(32.0),
+                        ..Default::default()
+                    }),
+                )
+                .set_parent(playfield);
+        }
+        GameMode::Race => {
+            let player_count = game_mode.player_count();
+            let mut sections = vec![TextSection::new(
+                format!("{}\n", strings.get("launch.race")),
+                theme.text_style(&font_assets.font_fira, 32.0, theme.colors.accent),
+            )];
+            sections.extend(PlayerId::all(player_count).map(|player| {
+                TextSection::new(
+                    format!(
+                        "{}\n",
+                        strings.format(
+                            "launch.race_player",
+                            &[
+                                ("player", &player.name(&strings)),
+                                (
+                                    "controls",
+                                    &PlayerControls::for_player(player, player_count)
+                                        .describe(&strings),
+                                ),
+                            ],
+                        )
+                    ),
+                    theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                )
+            }));
+
+            commands
+                .spawn(TextBundle::from_sections(sections).with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(SPEED_BAR_TOP),
+                    left: Val::Px(32.0),
+                    ..Default::default()
+                }))
+                .set_parent(playfield);
+        }
+    }
+}
+
+fn score_multiplier_text(strings: &Strings, selected_modifiers: &SelectedModifiers) -> String {
+    strings.format(
+        "launch.score_multiplier",
+        &[(
+            "multiplier",
+            &format!("{:.2}", selected_modifiers.0.score_multiplier()),
+        )],
+    )
+}
+
+fn option_color(theme: &Theme, enabled: bool) -> Color {
+    if enabled {
+        theme.colors.positive
+    } else {
+        theme.colors.muted
+    }
+}
+
+fn toggle_modifiers(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_mode: Res<GameMode>,
+    mut selected_modifiers: ResMut<SelectedModifiers>,
+) {
+    // the daily challenge decides its own modifiers
+    if *game_mode != GameMode::Endless {
+        return;
+    }
+
+    Modifier::ALL
+        .iter()
+        .zip(OPTION_KEYS.iter())
+        .for_each(|(modifier, key)| {
+            if keyboard_input.just_pressed(*key) {
+                selected_modifiers.0.toggle(*modifier);
+            }
+        });
+}
+
+fn update_modifiers_ui(
+    selected_modifiers: Res<SelectedModifiers>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    mut toggle_query: Query<(&mut Text, &ModifierToggleUi), Without<ModifierMultiplierUi>>,
+    mut multiplier_query: Query<&mut Text, With<ModifierMultiplierUi>>,
+) {
+    if !selected_modifiers.is_changed() {
+        return;
+    }
+
+    toggle_query.for_each_mut(|(mut text, toggle)| {
+        text.sections[0].style.color = option_color(&theme, selected_modifiers.0.has(toggle.0));
+    });
+
+    multiplier_query.for_each_mut(|mut text| {
+        text.sections[0].value = score_multiplier_text(&strings, &selected_modifiers);
+    });
 }
 
 fn update_speed(time: Res<Time>, mut speed: ResMut<CoinLaunchSpeedPercentage>) {
@@ -136,10 +481,50 @@ fn update_speed_ui(
     });
 }
 
-fn launch_coin(keyboard_input: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+fn launch_coin(
+    keyboard_input: Res<Input<KeyCode>>,
+    upgrades: Res<Upgrades>,
+    mut speed: ResMut<CoinLaunchSpeedPercentage>,
+    mut transition: ResMut<ScreenTransition>,
+) {
     if !keyboard_input.just_pressed(KeyCode::Space) {
         return;
     }
 
-    next_state.set(AppState::Ingame);
+    if speed.0 >= upgrades.launch_sweet_spot() {
+        speed.0 = 1.0;
+    }
+
+    transition.go_to(AppState::Ingame);
+}
+
+fn select_time_attack_goal(keyboard_input: Res<Input<KeyCode>>, mut game_mode: ResMut<GameMode>) {
+    if !matches!(*game_mode, GameMode::TimeAttack(_)) {
+        return;
+    }
+
+    TIME_ATTACK_GOALS
+        .iter()
+        .zip(OPTION_KEYS.iter())
+        .for_each(|(goal, key)| {
+            if keyboard_input.just_pressed(*key) {
+                *game_mode = GameMode::TimeAttack(*goal);
+            }
+        });
+}
+
+fn update_time_attack_goals_ui(
+    game_mode: Res<GameMode>,
+    theme: Res<Theme>,
+    mut query: Query<(&mut Text, &TimeAttackGoalUi)>,
+) {
+    if !game_mode.is_changed() {
+        return;
+    }
+
+    if let GameMode::TimeAttack(selected_goal) = *game_mode {
+        query.for_each_mut(|(mut text, goal_ui)| {
+            text.sections[0].style.color = option_color(&theme, goal_ui.0 == selected_goal);
+        });
+    }
 }
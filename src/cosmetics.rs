@@ -0,0 +1,283 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    achievements::UnlockedAchievements,
+    app_state::{AppState, StateOwner},
+    game_assets::TextureAssets,
+    locale::Strings,
+    physics::RelativeCoinY,
+    player::PlayerId,
+    scores::Scores,
+    storage,
+};
+
+pub struct CosmeticsPlugin;
+
+impl Plugin for CosmeticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SelectedCosmetics::load())
+            .add_systems(Update, (emit_trail_particles, update_trail_particles));
+    }
+}
+
+/// How a skin or trail is unlocked.
+pub enum Unlock {
+    Always,
+    Achievement(&'static str),
+    /// Best altitude (in metres) to reach.
+    Altitude(i32),
+}
+
+impl Unlock {
+    pub fn is_unlocked(&self, achievements: &UnlockedAchievements, scores: &Scores) -> bool {
+        match *self {
+            Unlock::Always => true,
+            Unlock::Achievement(id) => achievements.is_unlocked(id),
+            Unlock::Altitude(altitude) => scores.best_altitude >= altitude,
+        }
+    }
+
+    pub fn describe(&self, strings: &Strings) -> String {
+        match *self {
+            Unlock::Always => String::new(),
+            Unlock::Achievement(_) => strings.get("unlock.achievement").to_string(),
+            Unlock::Altitude(altitude) => {
+                strings.format("unlock.altitude", &[("altitude", &altitude)])
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum CoinSkin {
+    #[default]
+    Gold,
+    Silver,
+    Rose,
+    Emerald,
+}
+
+impl CoinSkin {
+    pub const ALL: [CoinSkin; 4] = [
+        CoinSkin::Gold,
+        CoinSkin::Silver,
+        CoinSkin::Rose,
+        CoinSkin::Emerald,
+    ];
+
+    pub fn name<'a>(&self, strings: &'a Strings) -> &'a str {
+        strings.get(match self {
+            CoinSkin::Gold => "skin.gold",
+            CoinSkin::Silver => "skin.silver",
+            CoinSkin::Rose => "skin.rose",
+            CoinSkin::Emerald => "skin.emerald",
+        })
+    }
+
+    pub fn unlock(&self) -> Unlock {
+        match self {
+            CoinSkin::Gold => Unlock::Always,
+            CoinSkin::Silver => Unlock::Altitude(1000),
+            CoinSkin::Rose => Unlock::Achievement("fae_friends"),
+            CoinSkin::Emerald => Unlock::Achievement("clear_skies"),
+        }
+    }
+
+    pub fn spin_atlas(&self, texture_assets: &TextureAssets) -> Handle<TextureAtlas> {
+        match self {
+            CoinSkin::Gold => texture_assets.atlas_coin_spin.clone(),
+            CoinSkin::Silver => texture_assets.atlas_coin_silver_spin.clone(),
+            CoinSkin::Rose => texture_assets.atlas_coin_rose_spin.clone(),
+            CoinSkin::Emerald => texture_assets.atlas_coin_emerald_spin.clone(),
+        }
+    }
+
+    pub fn texture(&self, texture_assets: &TextureAssets) -> Handle<Image> {
+        match self {
+            CoinSkin::Gold => texture_assets.texture_coin.clone(),
+            CoinSkin::Silver => texture_assets.texture_coin_silver.clone(),
+            CoinSkin::Rose => texture_assets.texture_coin_rose.clone(),
+            CoinSkin::Emerald => texture_assets.texture_coin_emerald.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum TrailStyle {
+    #[default]
+    None,
+    Sparkles,
+    Embers,
+    Rainbow,
+}
+
+impl TrailStyle {
+    pub const ALL: [TrailStyle; 4] = [
+        TrailStyle::None,
+        TrailStyle::Sparkles,
+        TrailStyle::Embers,
+        TrailStyle::Rainbow,
+    ];
+
+    pub fn name<'a>(&self, strings: &'a Strings) -> &'a str {
+        strings.get(match self {
+            TrailStyle::None => "trail.none",
+            TrailStyle::Sparkles => "trail.sparkles",
+            TrailStyle::Embers => "trail.embers",
+            TrailStyle::Rainbow => "trail.rainbow",
+        })
+    }
+
+    pub fn unlock(&self) -> Unlock {
+        match self {
+            TrailStyle::None => Unlock::Always,
+            TrailStyle::Sparkles => Unlock::Altitude(500),
+            TrailStyle::Embers => Unlock::Achievement("self_made"),
+            TrailStyle::Rainbow => Unlock::Altitude(2000),
+        }
+    }
+
+    /// Colour of a particle emitted `elapsed` seconds into the trail.
+    fn color(&self, elapsed: f32) -> Color {
+        match self {
+            TrailStyle::None => Color::NONE,
+            TrailStyle::Sparkles => Color::rgba(1.0, 1.0, 0.8, 0.8),
+            TrailStyle::Embers => Color::rgba(1.0, 0.45, 0.1, 0.8),
+            TrailStyle::Rainbow => Color::hsla((elapsed * 240.0) % 360.0, 0.9, 0.6, 0.8),
+        }
+    }
+
+    fn particle_size(&self) -> f32 {
+        match self {
+            TrailStyle::Embers => 28.0,
+            _ => 20.0,
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct SelectedCosmetics {
+    pub skin: CoinSkin,
+    pub trail: TrailStyle,
+}
+
+const COSMETICS_SAVE_KEY: &str = "cosmetics";
+
+impl SelectedCosmetics {
+    fn load() -> Self {
+        storage::load(COSMETICS_SAVE_KEY).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(COSMETICS_SAVE_KEY, self);
+    }
+}
+
+/// Leaves a trail of particles behind the entity.
+#[derive(Component)]
+pub struct TrailEmitter {
+    pub style: TrailStyle,
+    timer: Timer,
+}
+
+const TRAIL_EMIT_INTERVAL: f32 = 0.05;
+const TRAIL_PARTICLE_LIFETIME: f32 = 0.6;
+
+impl TrailEmitter {
+    pub fn new(style: TrailStyle) -> Self {
+        Self {
+            style,
+            timer: Timer::from_seconds(TRAIL_EMIT_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+#[derive(Component)]
+struct TrailParticle(Timer);
+
+fn emit_trail_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    state: Res<State<AppState>>,
+    texture_assets: Option<Res<TextureAssets>>,
+    mut query: Query<(
+        &mut TrailEmitter,
+        &GlobalTransform,
+        Option<&PlayerId>,
+        Option<&RenderLayers>,
+    )>,
+) {
+    let texture_assets = match texture_assets {
+        Some(texture_assets) => texture_assets,
+        None => return,
+    };
+
+    query.for_each_mut(|(mut emitter, transform, player, render_layers)| {
+        if emitter.style == TrailStyle::None || !emitter.timer.tick(time.delta()).just_finished() {
+            return;
+        }
+
+        let size = emitter.style.particle_size();
+        let mut translation = transform.translation();
+        translation.z -= 0.1;
+
+        let mut particle = commands.spawn((
+            SpriteBundle {
+                texture: texture_assets.texture_glow.clone(),
+                sprite: Sprite {
+                    color: emitter.style.color(time.elapsed_seconds()),
+                    custom_size: Some(Vec2::new(size, size)),
+                    ..Default::default()
+                },
+                transform: Transform::from_translation(translation),
+                ..Default::default()
+            },
+            TrailParticle(Timer::from_seconds(
+                TRAIL_PARTICLE_LIFETIME,
+                TimerMode::Once,
+            )),
+            StateOwner(*state.get()),
+        ));
+
+        if let Some(player) = player {
+            particle.insert((RelativeCoinY, *player));
+        }
+        if let Some(render_layers) = render_layers {
+            particle.insert(*render_layers);
+        }
+    });
+}
+
+const TRAIL_PARTICLE_DRIFT_SPEED: f32 = 60.0;
+
+fn update_trail_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(
+        &mut TrailParticle,
+        &mut Sprite,
+        &mut Transform,
+        Option<&RelativeCoinY>,
+        Entity,
+    )>,
+) {
+    query.for_each_mut(
+        |(mut particle, mut sprite, mut transform, relative, entity)| {
+            particle.0.tick(time.delta());
+
+            if particle.0.finished() {
+                commands.entity(entity).despawn();
+                return;
+            }
+
+            // Outside of a flight nothing scrolls, so let the particles drift down on their own.
+            if relative.is_none() {
+                transform.translation.y -= TRAIL_PARTICLE_DRIFT_SPEED * time.delta_seconds();
+            }
+            transform.scale = Vec3::splat(particle.0.percent_left());
+            sprite.color.set_a(0.8 * particle.0.percent_left());
+        },
+    );
+}
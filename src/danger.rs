@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::{in_flight, AppState},
+    coin::{Coin, COIN_LOSE_SPEED},
+    math::lerp,
+    sfx::{self, Sfx},
+};
+
+pub struct DangerPlugin;
+
+impl Plugin for DangerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            play_heartbeat
+                .run_if(in_state(AppState::Ingame))
+                .run_if(in_flight),
+        );
+    }
+}
+
+/// Falling faster than this warns that the flight is about to end.
+const DANGER_WARNING_SPEED: f32 = COIN_LOSE_SPEED * 0.4;
+
+const HEARTBEAT_SLOWEST_INTERVAL: f32 = 0.9;
+const HEARTBEAT_FASTEST_INTERVAL: f32 = 0.35;
+
+/// How close the coin is to being out, 0 until it falls faster than the warning speed, 1 when
+/// it is out.
+pub fn danger_level(coin: &Coin) -> f32 {
+    ((DANGER_WARNING_SPEED - coin.speed) / (DANGER_WARNING_SPEED - COIN_LOSE_SPEED)).clamp(0.0, 1.0)
+}
+
+/// Beats faster the closer the coin in the most danger is to being out.
+fn play_heartbeat(
+    mut commands: Commands,
+    time: Res<Time>,
+    sfx: Res<Sfx>,
+    coin_query: Query<&Coin>,
+    mut next_beat: Local<f32>,
+) {
+    let level = coin_query.iter().map(danger_level).fold(0.0, f32::max);
+    if level <= 0.0 {
+        // so the first beat comes right when the danger starts
+        *next_beat = 0.0;
+        return;
+    }
+
+    *next_beat -= time.delta_seconds();
+    if *next_beat <= 0.0 {
+        sfx::play(&mut commands, &sfx.heartbeat);
+        *next_beat = lerp(
+            HEARTBEAT_SLOWEST_INTERVAL,
+            HEARTBEAT_FASTEST_INTERVAL,
+            level,
+        );
+    }
+}
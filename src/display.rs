@@ -0,0 +1,221 @@
+use bevy::{
+    prelude::*,
+    render::camera::{CameraUpdateSystem, ScalingMode, Viewport},
+    ui::UiSystem,
+    window::{PrimaryWindow, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{Setting, SettingChangedEvent, Settings};
+
+pub struct DisplayPlugin;
+
+impl Plugin for DisplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Screen>()
+            .add_systems(PreUpdate, (apply_window_mode, update_screen).chain())
+            // late, so nodes and cameras spawned this frame are already placed when drawn
+            .add_systems(
+                PostUpdate,
+                (
+                    fit_playfield_ui.before(UiSystem::Layout),
+                    fit_playfield_viewports.before(CameraUpdateSystem),
+                ),
+            )
+            .add_systems(Update, toggle_fullscreen);
+    }
+}
+
+/// Size of the playfield in world units, everything is laid out for it and scaled to the window.
+pub const PLAYFIELD_WIDTH: f32 = 960.0;
+pub const PLAYFIELD_HEIGHT: f32 = 720.0;
+
+/// Steps of the UI scale option, on top of the scaling to the window size.
+const UI_SCALES: [f32; 4] = [0.75, 1.0, 1.25, 1.5];
+
+/// Display choices of the player.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct DisplayOptions {
+    pub fullscreen: bool,
+    pub ui_scale: f32,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            ui_scale: 1.0,
+        }
+    }
+}
+
+impl DisplayOptions {
+    pub fn next_ui_scale(&self) -> f32 {
+        let index = UI_SCALES
+            .iter()
+            .position(|scale| *scale == self.ui_scale)
+            .map_or(0, |index| index + 1);
+
+        UI_SCALES[index % UI_SCALES.len()]
+    }
+}
+
+/// Where things go in the window, in UI units (logical pixels divided by the `UiScale`).
+#[derive(Resource, Default, PartialEq)]
+pub struct Screen {
+    /// Size of the whole window.
+    pub size: Vec2,
+    /// Part of the window showing the playfield, with bars around it if the aspect differs.
+    pub playfield: Rect,
+    /// Physical position and size of the playfield, for camera viewports.
+    playfield_viewport: (UVec2, UVec2),
+}
+
+/// UI node that is kept over the playfield, for UI that belongs to the game world rather than
+/// the window edges. Its children can be laid out as if the window had the playfield size.
+#[derive(Component)]
+pub struct PlayfieldUi;
+
+/// Camera that renders into the playfield, or a vertical slice of it when it is split between
+/// `count` cameras.
+#[derive(Component)]
+pub struct PlayfieldViewport {
+    pub index: u32,
+    pub count: u32,
+}
+
+impl PlayfieldViewport {
+    /// Projection showing this camera's part of the playfield, whatever the window size.
+    pub fn projection(&self) -> OrthographicProjection {
+        OrthographicProjection {
+            scaling_mode: ScalingMode::Fixed {
+                width: PLAYFIELD_WIDTH / self.count as f32,
+                height: PLAYFIELD_HEIGHT,
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// Projection for the menu screens: the playfield always fits, and wider or taller windows show
+/// more around it.
+pub fn fit_projection() -> OrthographicProjection {
+    OrthographicProjection {
+        scaling_mode: ScalingMode::AutoMin {
+            min_width: PLAYFIELD_WIDTH,
+            min_height: PLAYFIELD_HEIGHT,
+        },
+        ..Default::default()
+    }
+}
+
+fn apply_window_mode(
+    settings: Res<Settings>,
+    mut setting_changed_events: EventReader<SettingChangedEvent>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !setting_changed_events
+        .iter()
+        .any(|event| event.0 == Setting::Fullscreen)
+    {
+        return;
+    }
+    let mut window = match window_query.get_single_mut() {
+        Ok(window) => window,
+        Err(_) => return,
+    };
+
+    let mode = if settings.display.fullscreen {
+        WindowMode::BorderlessFullscreen
+    } else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+}
+
+fn toggle_fullscreen(keyboard_input: Res<Input<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keyboard_input.just_pressed(KeyCode::F11) {
+        settings.display.fullscreen = !settings.display.fullscreen;
+    }
+}
+
+fn update_screen(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    settings: Res<Settings>,
+    mut ui_scale: ResMut<UiScale>,
+    mut screen: ResMut<Screen>,
+) {
+    let window = match window_query.get_single() {
+        Ok(window) => window,
+        Err(_) => return,
+    };
+    let window_size = Vec2::new(window.width(), window.height());
+    if window_size.min_element() <= 0.0 {
+        // minimized
+        return;
+    }
+
+    let fit_scale = (window_size / Vec2::new(PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT)).min_element();
+    let playfield_size = Vec2::new(PLAYFIELD_WIDTH, PLAYFIELD_HEIGHT) * fit_scale;
+    let playfield_min = (window_size - playfield_size) * 0.5;
+
+    let scale = (fit_scale * settings.display.ui_scale) as f64;
+    if ui_scale.scale != scale {
+        ui_scale.scale = scale;
+    }
+
+    let to_ui = |logical: Vec2| logical / scale as f32;
+    let to_physical = |logical: Vec2| (logical * window.scale_factor() as f32).round().as_uvec2();
+    screen.set_if_neq(Screen {
+        size: to_ui(window_size),
+        playfield: Rect::from_corners(to_ui(playfield_min), to_ui(playfield_min + playfield_size)),
+        playfield_viewport: (
+            to_physical(playfield_min),
+            to_physical(playfield_min + playfield_size) - to_physical(playfield_min),
+        ),
+    });
+}
+
+fn fit_playfield_ui(screen: Res<Screen>, mut query: Query<(&mut Style, Ref<PlayfieldUi>)>) {
+    query.for_each_mut(|(mut style, marker)| {
+        if !screen.is_changed() && !marker.is_added() {
+            return;
+        }
+
+        style.position_type = PositionType::Absolute;
+        style.left = Val::Px(screen.playfield.min.x);
+        style.top = Val::Px(screen.playfield.min.y);
+        style.width = Val::Px(screen.playfield.width());
+        style.height = Val::Px(screen.playfield.height());
+    });
+}
+
+fn fit_playfield_viewports(
+    screen: Res<Screen>,
+    mut query: Query<(&mut Camera, &PlayfieldViewport)>,
+) {
+    let (position, size) = screen.playfield_viewport;
+    if size.min_element() == 0 {
+        return;
+    }
+
+    query.for_each_mut(|(mut camera, slice)| {
+        let width = size.x / slice.count;
+        let viewport = Viewport {
+            physical_position: UVec2::new(position.x + width * slice.index, position.y),
+            physical_size: UVec2::new(width, size.y),
+            ..Default::default()
+        };
+
+        let unchanged = camera.viewport.as_ref().is_some_and(|current| {
+            current.physical_position == viewport.physical_position
+                && current.physical_size == viewport.physical_size
+        });
+        if !unchanged {
+            camera.viewport = Some(viewport);
+        }
+    });
+}
@@ -0,0 +1,233 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    app_state::{in_flight, AppState, FlightPhase, StateOwner},
+    boost_item::BoostItem,
+    cloud::Cloud,
+    coin_camera::CoinCamera,
+    display::{PlayfieldUi, PLAYFIELD_HEIGHT, PLAYFIELD_WIDTH},
+    fairy::Fairy,
+    game_assets::TextureAssets,
+    game_mode::GameMode,
+    level::SPAWN_MAX_Y,
+    math::lerp,
+    modifiers::{ActiveModifiers, Modifier},
+    player::PlayerId,
+    theme::Theme,
+};
+
+pub struct EdgeMarkersPlugin;
+
+impl Plugin for EdgeMarkersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Ingame), setup_edge_markers)
+            .add_systems(OnEnter(FlightPhase::Landing), hide_edge_markers)
+            .add_systems(
+                Update,
+                update_edge_markers
+                    .run_if(in_state(AppState::Ingame))
+                    .run_if(in_flight),
+            );
+    }
+}
+
+/// Markers per player, only the closest objects get one.
+const EDGE_MARKER_COUNT: usize = 10;
+const EDGE_MARKER_MAX_SIZE: f32 = 36.0;
+const EDGE_MARKER_MIN_SIZE: f32 = 14.0;
+const EDGE_MARKER_MIN_ALPHA: f32 = 0.3;
+/// Objects are spawned at most this far above the coin, so this covers all of them.
+const EDGE_MARKER_LOOKAHEAD: f32 = SPAWN_MAX_Y;
+
+/// Shows where an object above the view will come in, at the top edge of the view.
+#[derive(Component)]
+struct EdgeMarker(usize);
+
+#[derive(Clone, Copy)]
+enum MarkerKind {
+    Cloud,
+    Fairy,
+    BoostItem,
+}
+
+impl MarkerKind {
+    fn texture(&self, texture_assets: &TextureAssets) -> Handle<Image> {
+        match self {
+            MarkerKind::Cloud => texture_assets.texture_single_cloud.clone(),
+            MarkerKind::Fairy => texture_assets.texture_fairy.clone(),
+            MarkerKind::BoostItem => texture_assets.texture_boost.clone(),
+        }
+    }
+
+    fn color(&self, theme: &Theme) -> Color {
+        match self {
+            MarkerKind::Cloud => theme.colors.hazard,
+            MarkerKind::Fairy | MarkerKind::BoostItem => theme.colors.pickup,
+        }
+    }
+
+    /// Height of the icon for its width.
+    fn aspect(&self) -> f32 {
+        match self {
+            MarkerKind::Cloud => 0.5,
+            MarkerKind::Fairy | MarkerKind::BoostItem => 1.0,
+        }
+    }
+}
+
+/// An object above the view, `distance` above its top edge.
+struct Approaching {
+    kind: MarkerKind,
+    x: f32,
+    distance: f32,
+}
+
+#[derive(SystemParam)]
+struct ApproachingObjects<'w, 's> {
+    modifiers: Res<'w, ActiveModifiers>,
+    cloud_query: Query<'w, 's, (&'static Cloud, &'static Transform, &'static PlayerId)>,
+    fairy_query: Query<'w, 's, (&'static Transform, &'static PlayerId), With<Fairy>>,
+    boost_item_query: Query<'w, 's, (&'static Transform, &'static PlayerId), With<BoostItem>>,
+}
+
+impl ApproachingObjects<'_, '_> {
+    /// Closest first.
+    fn above(&self, view_top: f32, player: &PlayerId) -> Vec<Approaching> {
+        // the clouds are meant to be a surprise then
+        let clouds_hidden = self.modifiers.has(Modifier::InvisibleClouds);
+
+        let clouds = self
+            .cloud_query
+            .iter()
+            .filter(|(cloud, _, _)| !clouds_hidden && cloud.is_active())
+            .map(|(_, transform, object_player)| (MarkerKind::Cloud, transform, object_player));
+        let fairies = self
+            .fairy_query
+            .iter()
+            .map(|(transform, object_player)| (MarkerKind::Fairy, transform, object_player));
+        let boost_items = self
+            .boost_item_query
+            .iter()
+            .map(|(transform, object_player)| (MarkerKind::BoostItem, transform, object_player));
+
+        let mut approaching = clouds
+            .chain(fairies)
+            .chain(boost_items)
+            .filter(|(_, _, object_player)| *object_player == player)
+            .map(|(kind, transform, _)| Approaching {
+                kind,
+                x: transform.translation.x,
+                distance: transform.translation.y - view_top,
+            })
+            .filter(|object| object.distance > 0.0 && object.distance < EDGE_MARKER_LOOKAHEAD)
+            .collect::<Vec<_>>();
+        approaching.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        approaching
+    }
+}
+
+fn setup_edge_markers(mut commands: Commands, game_mode: Res<GameMode>) {
+    let player_count = game_mode.player_count();
+
+    let playfield = commands
+        .spawn((
+            NodeBundle::default(),
+            PlayfieldUi,
+            StateOwner(AppState::Ingame),
+        ))
+        .id();
+
+    PlayerId::all(player_count).for_each(|player| {
+        commands
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(100.0 * player.0 as f32 / player_count as f32),
+                    width: Val::Percent(100.0 / player_count as f32),
+                    height: Val::Percent(100.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .set_parent(playfield)
+            .with_children(|parent| {
+                (0..EDGE_MARKER_COUNT).for_each(|index| {
+                    parent.spawn((
+                        ImageBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                top: Val::Px(4.0),
+                                display: Display::None,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        EdgeMarker(index),
+                        player,
+                    ));
+                });
+            });
+    });
+}
+
+fn hide_edge_markers(mut query: Query<&mut Style, With<EdgeMarker>>) {
+    query.for_each_mut(|mut style| {
+        style.display = Display::None;
+    });
+}
+
+/// Closer objects get bigger and more opaque markers.
+fn update_edge_markers(
+    game_mode: Res<GameMode>,
+    texture_assets: Res<TextureAssets>,
+    theme: Res<Theme>,
+    objects: ApproachingObjects,
+    camera_query: Query<(&Transform, &PlayerId), With<CoinCamera>>,
+    mut query: Query<(
+        &mut Style,
+        &mut UiImage,
+        &mut BackgroundColor,
+        &EdgeMarker,
+        &PlayerId,
+    )>,
+) {
+    let view_width = PLAYFIELD_WIDTH / game_mode.player_count() as f32;
+
+    camera_query.for_each(|(camera_transform, camera_player)| {
+        let view_top = camera_transform.translation.y + PLAYFIELD_HEIGHT * 0.5;
+        let approaching = objects.above(view_top, camera_player);
+
+        query
+            .iter_mut()
+            .filter(|(_, _, _, _, player)| *player == camera_player)
+            .for_each(|(mut style, mut image, mut background, marker, _)| {
+                let object = match approaching.get(marker.0) {
+                    Some(object) => object,
+                    None => {
+                        if style.display != Display::None {
+                            style.display = Display::None;
+                        }
+                        return;
+                    }
+                };
+
+                let t = object.distance / EDGE_MARKER_LOOKAHEAD;
+                let size = lerp(EDGE_MARKER_MAX_SIZE, EDGE_MARKER_MIN_SIZE, t);
+                style.display = Display::Flex;
+                style.left = Val::Percent((object.x / view_width + 0.5).clamp(0.0, 1.0) * 100.0);
+                style.margin = UiRect::left(Val::Px(-size * 0.5));
+                style.width = Val::Px(size);
+                style.height = Val::Px(size * object.kind.aspect());
+
+                let texture = object.kind.texture(&texture_assets);
+                if image.texture != texture {
+                    image.texture = texture;
+                }
+                background.0 =
+                    object
+                        .kind
+                        .color(&theme)
+                        .with_a(lerp(1.0, EDGE_MARKER_MIN_ALPHA, t));
+            });
+    });
+}
@@ -1,9 +1,17 @@
-use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+use bevy::{core_pipeline::clear_color::ClearColorConfig, ecs::system::SystemParam, prelude::*};
 
 use crate::{
+    anim::{Ease, Tween, TweenMode, TweenTarget},
     app_state::{AppState, StateOwner},
+    display::fit_projection,
     game_assets::FontAssets,
+    game_mode::{DailyChallenge, GameMode},
+    locale::Strings,
+    player::PlayerId,
     scores::Scores,
+    theme::Theme,
+    time_attack::TimeAttackRun,
+    transition::{not_leaving_state, ScreenTransition},
 };
 
 pub struct EndUiPlugin;
@@ -11,19 +19,154 @@ pub struct EndUiPlugin;
 impl Plugin for EndUiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::End), setup_end_ui)
-            .add_systems(Update, restart_game.run_if(in_state(AppState::End)));
+            .add_systems(
+                Update,
+                (restart_game, return_to_main_menu)
+                    .run_if(in_state(AppState::End))
+                    .run_if(not_leaving_state),
+            );
     }
 }
 
-const BACKGROUND_COLOR: Color = Color::rgb(40.0 / 255.0, 40.0 / 255.0, 63.0 / 255.0);
+const TITLE_ENTRANCE_SECONDS: f32 = 0.5;
+const DETAIL_FADE_SECONDS: f32 = 0.3;
+const DETAIL_STAGGER_SECONDS: f32 = 0.15;
+const NEW_BEST_BLINK_SECONDS: f32 = 0.4;
+
+/// Everything the end screen reports about the flight that just ended.
+#[derive(SystemParam)]
+struct FlightResults<'w> {
+    scores: Res<'w, Scores>,
+    game_mode: Res<'w, GameMode>,
+    daily: Res<'w, DailyChallenge>,
+    time_attack: Res<'w, TimeAttackRun>,
+}
+
+fn setup_end_ui(
+    mut commands: Commands,
+    results: FlightResults,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+) {
+    let FlightResults {
+        scores,
+        game_mode,
+        daily,
+        time_attack,
+    } = results;
+
+    let mut title = strings.get("end.title").to_string();
+    let mut score_text = if (scores.end_multiplier - 1.0).abs() > f32::EPSILON {
+        strings.format(
+            "end.final_score_modifiers",
+            &[
+                ("score", &scores.end_score),
+                ("multiplier", &format!("{:.2}", scores.end_multiplier)),
+            ],
+        )
+    } else {
+        strings.format("end.final_score", &[("score", &scores.end_score)])
+    };
+    let mut new_record = scores.new_record;
+
+    let mut detail_texts =
+        vec![strings.format("end.max_altitude", &[("altitude", &scores.end_altitude)])];
+
+    let best_text = match *game_mode {
+        GameMode::Endless => {
+            detail_texts = vec![if scores.new_altitude_record {
+                strings.format(
+                    "end.max_altitude_new_best",
+                    &[("altitude", &scores.end_altitude)],
+                )
+            } else {
+                strings.format(
+                    "end.max_altitude_best",
+                    &[
+                        ("altitude", &scores.end_altitude),
+                        ("best", &scores.best_altitude),
+                    ],
+                )
+            }];
+            strings.format("end.your_best", &[("score", &scores.best_score)])
+        }
+        GameMode::Daily if daily.scored => strings.format(
+            "end.daily_best",
+            &[("score", &scores.daily_best(daily.day))],
+        ),
+        GameMode::Daily => strings.format(
+            "end.daily_best_practice",
+            &[("score", &scores.daily_best(daily.day))],
+        ),
+        GameMode::TimeAttack(goal) => {
+            if time_attack.finished {
+                title = strings.get("end.goal_reached").to_string();
+                score_text = strings.format(
+                    "end.time",
+                    &[("time", &format!("{:.2}", time_attack.elapsed))],
+                );
+            } else {
+                title = strings.get("end.out_of_speed").to_string();
+                score_text = strings.format(
+                    "end.reached",
+                    &[("altitude", &scores.end_altitude), ("goal", &goal)],
+                );
+            }
+            new_record = time_attack.new_best;
+            detail_texts = (0..time_attack.splits.len())
+                .filter_map(|index| time_attack.describe_split(index, &strings))
+                .collect();
+
+            match &time_attack.personal_best {
+                Some(record) => strings.format(
+                    "end.previous_best",
+                    &[("time", &format!("{:.2}", record.time))],
+                ),
+                None => strings.get("end.no_previous_best").to_string(),
+            }
+        }
+        GameMode::Race => {
+            let best = scores.end_race_scores.iter().copied().max().unwrap_or(0);
+            let mut leaders = PlayerId::all(scores.end_race_scores.len())
+                .filter(|player| scores.end_race_scores[player.0] == best);
+
+            title = match (leaders.next(), leaders.next()) {
+                (Some(winner), None) => {
+                    strings.format("end.race_winner", &[("player", &winner.name(&strings))])
+                }
+                _ => strings.get("end.draw").to_string(),
+            };
+            score_text = strings.get("end.race_results").to_string();
+            new_record = false;
+            detail_texts = PlayerId::all(scores.end_race_scores.len())
+                .map(|player| {
+                    strings.format(
+                        "end.race_score",
+                        &[
+                            ("player", &player.name(&strings)),
+                            ("score", &scores.end_race_scores[player.0]),
+                        ],
+                    )
+                })
+                .collect();
+
+            strings.get("end.race_unranked").to_string()
+        }
+        GameMode::Tutorial => {
+            title = strings.get("end.tutorial").to_string();
+            new_record = false;
+
+            strings.get("end.tutorial_unranked").to_string()
+        }
+    };
 
-fn setup_end_ui(mut commands: Commands, scores: Res<Scores>, font_assets: Res<FontAssets>) {
     commands.spawn((
         Camera2dBundle {
             camera_2d: Camera2d {
-                clear_color: ClearColorConfig::Custom(BACKGROUND_COLOR),
-                ..Default::default()
+                clear_color: ClearColorConfig::Custom(theme.colors.background),
             },
+            projection: fit_projection(),
             ..Default::default()
         },
         StateOwner(AppState::End),
@@ -46,13 +189,20 @@ fn setup_end_ui(mut commands: Commands, scores: Res<Scores>, font_assets: Res<Fo
             StateOwner(AppState::End),
         ))
         .with_children(|parent| {
-            parent.spawn(TextBundle::from_section(
-                "Good Flight!",
-                TextStyle {
-                    font_size: 96.0,
-                    color: Color::CYAN,
-                    ..Default::default()
-                },
+            parent.spawn((
+                TextBundle::from_section(
+                    title,
+                    TextStyle {
+                        font_size: theme.font_size(96.0),
+                        color: theme.colors.title,
+                        ..Default::default()
+                    },
+                ),
+                Tween::new(
+                    TweenTarget::Scale(Vec3::ZERO, Vec3::ONE),
+                    TITLE_ENTRANCE_SECONDS,
+                    Ease::BackOut,
+                ),
             ));
 
             parent
@@ -68,13 +218,8 @@ fn setup_end_ui(mut commands: Commands, scores: Res<Scores>, font_assets: Res<Fo
                 })
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
-                        format!("Final Score: {}m", scores.end_score),
-                        TextStyle {
-                            font: font_assets.font_fira.clone(),
-                            font_size: 32.0,
-                            color: Color::WHITE,
-                            ..Default::default()
-                        },
+                        score_text,
+                        theme.text_style(&font_assets.font_fira, 32.0, theme.colors.text),
                     ));
 
                     parent
@@ -90,43 +235,98 @@ fn setup_end_ui(mut commands: Commands, scores: Res<Scores>, font_assets: Res<Fo
                         })
                         .with_children(|parent| {
                             parent.spawn(TextBundle::from_section(
-                                format!("Your Best: {}m", scores.best_score),
-                                TextStyle {
-                                    font: font_assets.font_fira.clone(),
-                                    font_size: 32.0,
-                                    color: Color::WHITE,
-                                    ..Default::default()
-                                },
+                                best_text,
+                                theme.text_style(&font_assets.font_fira, 32.0, theme.colors.text),
                             ));
 
-                            if scores.new_record {
-                                parent.spawn(TextBundle::from_section(
-                                    "(NEW BEST!)",
-                                    TextStyle {
-                                        font: font_assets.font_fira.clone(),
-                                        font_size: 28.0,
-                                        color: Color::YELLOW,
-                                        ..Default::default()
-                                    },
+                            if new_record {
+                                parent.spawn((
+                                    TextBundle::from_section(
+                                        strings.get("end.new_best"),
+                                        theme.text_style(
+                                            &font_assets.font_fira,
+                                            28.0,
+                                            theme.colors.accent,
+                                        ),
+                                    ),
+                                    Tween::new(
+                                        TweenTarget::TextColor(
+                                            theme.colors.accent,
+                                            theme.colors.text,
+                                        ),
+                                        NEW_BEST_BLINK_SECONDS,
+                                        Ease::Linear,
+                                    )
+                                    .then(
+                                        TweenTarget::TextColor(
+                                            theme.colors.text,
+                                            theme.colors.accent,
+                                        ),
+                                        NEW_BEST_BLINK_SECONDS,
+                                        Ease::Linear,
+                                    )
+                                    .with_mode(TweenMode::Loop),
                                 ));
                             }
                         });
+
+                    detail_texts
+                        .into_iter()
+                        .enumerate()
+                        .for_each(|(index, detail_text)| {
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    detail_text,
+                                    theme.text_style(
+                                        &font_assets.font_fira,
+                                        24.0,
+                                        theme.colors.text,
+                                    ),
+                                ),
+                                Tween::new(
+                                    TweenTarget::TextColor(Color::NONE, theme.colors.text),
+                                    DETAIL_FADE_SECONDS,
+                                    Ease::Linear,
+                                )
+                                .with_delay(
+                                    TITLE_ENTRANCE_SECONDS + index as f32 * DETAIL_STAGGER_SECONDS,
+                                ),
+                            ));
+                        });
                 });
 
             parent.spawn(TextBundle::from_section(
-                "Press [SPACE] to try again",
-                TextStyle {
-                    font: font_assets.font_fira.clone(),
-                    font_size: 40.0,
-                    color: Color::GREEN,
-                    ..Default::default()
-                },
+                strings.get("end.continue"),
+                theme.text_style(&font_assets.font_fira, 40.0, theme.colors.positive),
+            ));
+
+            parent.spawn(TextBundle::from_section(
+                strings.get("end.main_menu"),
+                theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
             ));
         });
 }
 
-fn restart_game(keyboard_input: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+fn restart_game(
+    keyboard_input: Res<Input<KeyCode>>,
+    game_mode: Res<GameMode>,
+    mut transition: ResMut<ScreenTransition>,
+) {
     if keyboard_input.just_pressed(KeyCode::Space) {
-        next_state.set(AppState::CoinLaunch);
+        // after the tutorial, the player picks their first real run from the menu
+        transition.go_to(match *game_mode {
+            GameMode::Tutorial => AppState::MainMenu,
+            _ => AppState::Shop,
+        });
+    }
+}
+
+/// The shop leads straight into the next launch, the other modes are only reachable from the menu.
+fn return_to_main_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        transition.go_to(AppState::MainMenu);
     }
 }
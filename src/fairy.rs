@@ -1,31 +1,47 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, render::view::RenderLayers};
 
 use crate::{
-    anim::AnimScale,
-    app_state::{AppState, StateOwner},
+    anim::{FrameAnimation, Tween},
+    app_state::{in_flight, AppState},
     coin::Coin,
-    game_assets::TextureAssets,
     physics::RelativeCoinY,
+    player::PlayerId,
+    pool::{PoolKind, PoolSpawner, Pools},
+    theme::Theme,
 };
 
 pub struct FairyPlugin;
 
 impl Plugin for FairyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_event::<FairyTouchedEvent>().add_systems(
             Update,
-            (init_fairies, check_fairy_coin_collision).run_if(in_state(AppState::Ingame)),
+            (
+                check_fairy_coin_collision.run_if(in_flight),
+                reset_reused_fairy_glows,
+            )
+                .run_if(in_state(AppState::Ingame)),
         );
     }
 }
 
 #[derive(Component)]
-pub struct InitFairy(pub Vec2);
+pub struct Fairy;
 
+/// Glow behind a fairy, it stays on the fairy while it is in its pool.
 #[derive(Component)]
-pub struct Fairy;
+struct FairyGlow;
+
+#[derive(Event)]
+pub struct FairyTouchedEvent {
+    pub player: PlayerId,
+}
+
+const FAIRY_FLAP_FRAMES: usize = 4;
+const FAIRY_FLAP_FPS: f32 = 8.0;
 
 const FAIRY_SIZE: Vec2 = Vec2::new(60.0, 60.0);
+const FAIRY_GLOW_SIZE: Vec2 = Vec2::new(96.0, 96.0);
 
 impl Fairy {
     pub fn get_bounds(transform: &Transform) -> Rect {
@@ -36,41 +52,80 @@ impl Fairy {
     }
 }
 
-fn init_fairies(
-    mut commands: Commands,
-    query: Query<(&InitFairy, Entity)>,
-    texture_assets: Res<TextureAssets>,
-) {
-    query.for_each(|(init_fairy, init_fairy_entity)| {
-        commands.get_entity(init_fairy_entity).unwrap().despawn();
+pub fn spawn_fairy(spawner: &mut PoolSpawner, position: Vec2, player: PlayerId) {
+    let bundle = (
+        SpriteSheetBundle {
+            texture_atlas: spawner.texture_assets.atlas_fairy_flap.clone(),
+            sprite: TextureAtlasSprite {
+                custom_size: Some(Vec2::new(110.0, 110.0)),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..Default::default()
+        },
+        FrameAnimation::new(FAIRY_FLAP_FRAMES, FAIRY_FLAP_FPS),
+        RelativeCoinY,
+        Fairy,
+        player,
+        player.render_layers(),
+    );
+
+    let (entity, is_new) = match spawner.acquire(PoolKind::Fairy, player) {
+        Some(acquired) => acquired,
+        None => return,
+    };
+    let glow_sprite = fairy_glow_sprite(&spawner.theme);
+    let glow_texture = spawner.texture_assets.texture_glow.clone();
 
-        let pos = Vec3::new(init_fairy.0.x, init_fairy.0.y, 0.0);
-        commands
-            .spawn((
+    let mut fairy = spawner.commands.entity(entity);
+    fairy.insert(bundle);
+    // a reused fairy keeps its glow, see `reset_reused_fairy_glows`
+    if is_new {
+        fairy.with_children(|parent| {
+            parent.spawn((
                 SpriteBundle {
-                    texture: texture_assets.texture_fairy.clone(),
-                    sprite: Sprite {
-                        custom_size: Some(Vec2::new(110.0, 110.0)),
-                        ..Default::default()
-                    },
-                    transform: Transform::from_translation(pos),
+                    texture: glow_texture,
+                    sprite: glow_sprite,
                     ..Default::default()
                 },
-                RelativeCoinY,
-                Fairy,
-                StateOwner(AppState::Ingame),
-            ))
-            .with_children(|parent| {
-                parent.spawn((
-                    SpriteBundle {
-                        texture: texture_assets.texture_glow.clone(),
-                        sprite: Sprite {
-                            custom_size: Some(Vec2::new(96.0, 96.0)),
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    },
-                    AnimScale::default(),
+                fairy_glow_tween(),
+                player.render_layers(),
+                FairyGlow,
+            ));
+        });
+    }
+}
+
+fn fairy_glow_sprite(theme: &Theme) -> Sprite {
+    Sprite {
+        color: theme.colors.pickup,
+        custom_size: Some(FAIRY_GLOW_SIZE),
+        ..Default::default()
+    }
+}
+
+fn fairy_glow_tween() -> Tween {
+    Tween::pulse(0.8, 1.2, 0.4)
+}
+
+/// The pools are shared by the players, so a reused fairy's glow may still be drawn for the
+/// player it had before.
+fn reset_reused_fairy_glows(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    fairy_query: Query<(&Children, &RenderLayers), Added<Fairy>>,
+    glow_query: Query<(), With<FairyGlow>>,
+) {
+    fairy_query.for_each(|(children, render_layers)| {
+        children
+            .iter()
+            .filter(|child| glow_query.contains(**child))
+            .for_each(|glow| {
+                commands.entity(*glow).insert((
+                    *render_layers,
+                    fairy_glow_sprite(&theme),
+                    fairy_glow_tween(),
+                    Transform::default(),
                 ));
             });
     });
@@ -81,21 +136,28 @@ const FAIRY_SPEED_BOOST: f32 = 400.0;
 
 fn check_fairy_coin_collision(
     mut commands: Commands,
-    mut coin_query: Query<(&mut Coin, &Transform)>,
-    fairy_query: Query<(&Transform, Entity), (With<Fairy>, Without<Coin>)>,
+    mut pools: ResMut<Pools>,
+    mut coin_query: Query<(&mut Coin, &Transform, &PlayerId)>,
+    fairy_query: Query<(&Transform, &PlayerId, Entity), With<Fairy>>,
+    mut fairy_touched_events: EventWriter<FairyTouchedEvent>,
 ) {
-    let (mut coin, coin_transform) = coin_query.single_mut();
-    let coin_rect = Coin::get_bounds(coin_transform);
-
-    fairy_query.for_each(|(fairy_transform, fairy_entity)| {
-        let fairy_rect = Fairy::get_bounds(fairy_transform);
-
-        if !fairy_rect.intersect(coin_rect).is_empty() {
-            coin.speed += FAIRY_SPEED_BOOST;
-            commands
-                .get_entity(fairy_entity)
-                .unwrap()
-                .despawn_recursive();
-        }
+    coin_query.for_each_mut(|(mut coin, coin_transform, coin_player)| {
+        let coin_rect = Coin::get_bounds(coin_transform);
+
+        fairy_query.for_each(|(fairy_transform, player, fairy_entity)| {
+            if player != coin_player {
+                return;
+            }
+
+            let fairy_rect = Fairy::get_bounds(fairy_transform);
+
+            if !fairy_rect.intersect(coin_rect).is_empty() {
+                coin.speed += FAIRY_SPEED_BOOST;
+                fairy_touched_events.send(FairyTouchedEvent {
+                    player: *coin_player,
+                });
+                pools.release(&mut commands, fairy_entity, PoolKind::Fairy, *player);
+            }
+        });
     });
 }
@@ -2,7 +2,7 @@ use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 use iyes_progress::ProgressPlugin;
 
-use crate::app_state::AppState;
+use crate::{achievements::AchievementDefinitions, app_state::AppState, locale::StringTable};
 
 pub struct GameAssetsPlugin;
 
@@ -11,7 +11,8 @@ impl Plugin for GameAssetsPlugin {
         app.add_plugins(ProgressPlugin::new(AppState::Loading).continue_to(AppState::MainMenu))
             .add_loading_state(LoadingState::new(AppState::Loading))
             .add_collection_to_loading_state::<_, FontAssets>(AppState::Loading)
-            .add_collection_to_loading_state::<_, TextureAssets>(AppState::Loading);
+            .add_collection_to_loading_state::<_, TextureAssets>(AppState::Loading)
+            .add_collection_to_loading_state::<_, DataAssets>(AppState::Loading);
     }
 }
 
@@ -19,18 +20,46 @@ impl Plugin for GameAssetsPlugin {
 pub struct FontAssets {
     #[asset(path = "fonts/FiraSans-Regular.ttf")]
     pub font_fira: Handle<Font>,
+    /// Used for the characters FiraSans doesn't have.
+    #[asset(path = "fonts/DejaVuSans.ttf")]
+    pub font_fallback: Handle<Font>,
 }
 
 #[derive(AssetCollection, Resource)]
 pub struct TextureAssets {
     #[asset(path = "textures/coin.png")]
     pub texture_coin: Handle<Image>,
+    #[asset(path = "textures/coin_silver.png")]
+    pub texture_coin_silver: Handle<Image>,
+    #[asset(path = "textures/coin_rose.png")]
+    pub texture_coin_rose: Handle<Image>,
+    #[asset(path = "textures/coin_emerald.png")]
+    pub texture_coin_emerald: Handle<Image>,
+
+    #[asset(texture_atlas(tile_size_x = 64., tile_size_y = 64., columns = 8, rows = 1))]
+    #[asset(path = "textures/coin_spin.png")]
+    pub atlas_coin_spin: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 64., tile_size_y = 64., columns = 8, rows = 1))]
+    #[asset(path = "textures/coin_silver_spin.png")]
+    pub atlas_coin_silver_spin: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 64., tile_size_y = 64., columns = 8, rows = 1))]
+    #[asset(path = "textures/coin_rose_spin.png")]
+    pub atlas_coin_rose_spin: Handle<TextureAtlas>,
+    #[asset(texture_atlas(tile_size_x = 64., tile_size_y = 64., columns = 8, rows = 1))]
+    #[asset(path = "textures/coin_emerald_spin.png")]
+    pub atlas_coin_emerald_spin: Handle<TextureAtlas>,
     #[asset(path = "textures/clouds.png")]
     pub texture_clouds: Handle<Image>,
     #[asset(path = "textures/boost.png")]
     pub texture_boost: Handle<Image>,
+    #[asset(texture_atlas(tile_size_x = 64., tile_size_y = 64., columns = 4, rows = 1))]
+    #[asset(path = "textures/boost_shimmer.png")]
+    pub atlas_boost_shimmer: Handle<TextureAtlas>,
     #[asset(path = "textures/fairy.png")]
     pub texture_fairy: Handle<Image>,
+    #[asset(texture_atlas(tile_size_x = 128., tile_size_y = 128., columns = 4, rows = 1))]
+    #[asset(path = "textures/fairy_flap.png")]
+    pub atlas_fairy_flap: Handle<TextureAtlas>,
     #[asset(path = "textures/glow.png")]
     pub texture_glow: Handle<Image>,
     #[asset(path = "textures/launcher.png")]
@@ -44,3 +73,14 @@ pub struct TextureAssets {
     #[asset(path = "textures/single_cloud.png")]
     pub texture_single_cloud: Handle<Image>,
 }
+
+#[derive(AssetCollection, Resource)]
+pub struct DataAssets {
+    #[asset(path = "data/game.achievements.ron")]
+    pub achievements: Handle<AchievementDefinitions>,
+    #[asset(
+        paths("data/en.strings.ron", "data/de.strings.ron", "data/fr.strings.ron"),
+        collection(typed)
+    )]
+    pub string_tables: Vec<Handle<StringTable>>,
+}
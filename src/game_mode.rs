@@ -0,0 +1,132 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    level::LevelSeed,
+    modifiers::{ActiveModifiers, Modifier, SelectedModifiers},
+    scores::Scores,
+};
+
+pub struct GameModePlugin;
+
+impl Plugin for GameModePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameMode::default())
+            .insert_resource(DailyChallenge::default())
+            .add_systems(OnExit(AppState::CoinLaunch), prepare_run);
+    }
+}
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Endless,
+    Daily,
+    /// Reach the goal altitude (in metres) as fast as possible.
+    TimeAttack(u32),
+    /// Two players race on the same level in split-screen.
+    Race,
+    /// Scripted first flight teaching the controls.
+    Tutorial,
+}
+
+impl GameMode {
+    pub fn player_count(&self) -> usize {
+        match self {
+            GameMode::Race => 2,
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct DailyChallenge {
+    pub day: u64,
+    /// Only the first daily attempt of the day counts towards the daily best.
+    pub scored: bool,
+}
+
+impl DailyChallenge {
+    fn seed(day: u64) -> u64 {
+        // spread consecutive days apart, so their levels don't look alike
+        day.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
+    pub fn modifiers(day: u64) -> ActiveModifiers {
+        let mut rng = StdRng::seed_from_u64(Self::seed(day));
+
+        let mut modifiers = ActiveModifiers::default();
+        Modifier::DAILY.iter().for_each(|modifier| {
+            if rng.gen_bool(0.5) {
+                modifiers.0.insert(*modifier);
+            }
+        });
+
+        if modifiers.0.is_empty() {
+            modifiers
+                .0
+                .insert(*Modifier::DAILY.choose(&mut rng).unwrap());
+        }
+
+        modifiers
+    }
+}
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs() / SECONDS_PER_DAY)
+        .unwrap_or_default()
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> u64 {
+    // wasm: std::time::SystemTime is not available in the browser
+    (js_sys::Date::now() / 1000.0) as u64 / SECONDS_PER_DAY
+}
+
+fn prepare_run(
+    game_mode: Res<GameMode>,
+    mut daily: ResMut<DailyChallenge>,
+    mut scores: ResMut<Scores>,
+    mut level_seed: ResMut<LevelSeed>,
+    selected_modifiers: Res<SelectedModifiers>,
+    mut modifiers: ResMut<ActiveModifiers>,
+) {
+    match *game_mode {
+        GameMode::Endless => {
+            level_seed.0 = rand::thread_rng().gen();
+            *modifiers = selected_modifiers.0.clone();
+        }
+        GameMode::Race => {
+            level_seed.0 = rand::thread_rng().gen();
+            *modifiers = Default::default();
+        }
+        GameMode::Daily => {
+            let day = today();
+
+            daily.day = day;
+            daily.scored = !scores.has_attempted_daily(day);
+            if daily.scored {
+                scores.register_daily_attempt(day);
+            }
+
+            level_seed.0 = DailyChallenge::seed(day);
+            *modifiers = DailyChallenge::modifiers(day);
+        }
+        GameMode::Tutorial => {
+            // the tutorial places its own objects
+            level_seed.0 = 0;
+            *modifiers = Default::default();
+        }
+        GameMode::TimeAttack(goal) => {
+            // same layout for every attempt, so times can be compared
+            level_seed.0 = goal as u64;
+            *modifiers = Default::default();
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    anim::{Ease, Tween, TweenTarget},
+    app_state::{in_flight, AppState, StateOwner},
+    coin::Coin,
+    game_assets::TextureAssets,
+    game_mode::GameMode,
+    physics::TimeScale,
+    player::PlayerId,
+    scores::Scores,
+    storage,
+};
+
+pub struct GhostPlugin;
+
+impl Plugin for GhostPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BestGhost::load())
+            .insert_resource(GhostRun::default())
+            .add_systems(
+                OnEnter(AppState::Ingame),
+                (reset_ghost_run, setup_ghost).run_if(in_endless),
+            )
+            .add_systems(
+                Update,
+                (record_ghost_run.run_if(in_flight), update_ghost)
+                    .chain()
+                    .run_if(in_state(AppState::Ingame))
+                    .run_if(in_endless),
+            )
+            .add_systems(OnEnter(AppState::End), save_best_ghost.run_if(in_endless));
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct GhostSample {
+    pub time: f32,
+    pub altitude: f32,
+    pub x: f32,
+}
+
+/// Trajectory of the best endless run.
+#[derive(Resource, Default)]
+pub struct BestGhost(Vec<GhostSample>);
+
+const GHOST_SAVE_KEY: &str = "ghost";
+
+impl BestGhost {
+    fn load() -> Self {
+        Self(storage::load(GHOST_SAVE_KEY).unwrap_or_default())
+    }
+
+    fn save(&self) {
+        storage::save(GHOST_SAVE_KEY, &self.0);
+    }
+
+    pub fn exists(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    /// Position of the ghost at `time`, holding the last sample once the ghost run is over.
+    fn sample_at(&self, time: f32) -> Option<GhostSample> {
+        let next_index = self.0.iter().position(|sample| sample.time > time);
+
+        match next_index {
+            Some(0) => self.0.first().copied(),
+            Some(index) => {
+                let previous = self.0[index - 1];
+                let next = self.0[index];
+                let t = (time - previous.time) / (next.time - previous.time);
+
+                Some(GhostSample {
+                    time,
+                    altitude: previous.altitude + (next.altitude - previous.altitude) * t,
+                    x: previous.x + (next.x - previous.x) * t,
+                })
+            }
+            None => self.0.last().copied(),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct GhostRun {
+    elapsed: f32,
+    since_last_sample: f32,
+    samples: Vec<GhostSample>,
+    /// How far ahead of the best run the coin is, in metres.
+    pub delta: Option<f32>,
+}
+
+#[derive(Component)]
+pub struct Ghost;
+
+const GHOST_SAMPLE_INTERVAL: f32 = 0.1;
+const GHOST_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.35);
+const GHOST_FADE_IN_SECONDS: f32 = 1.0;
+
+fn in_endless(game_mode: Res<GameMode>) -> bool {
+    *game_mode == GameMode::Endless
+}
+
+fn reset_ghost_run(mut run: ResMut<GhostRun>) {
+    *run = Default::default();
+}
+
+fn setup_ghost(
+    mut commands: Commands,
+    best_ghost: Res<BestGhost>,
+    texture_assets: Res<TextureAssets>,
+) {
+    if !best_ghost.exists() {
+        return;
+    }
+
+    let player = PlayerId(0);
+
+    commands.spawn((
+        SpriteBundle {
+            texture: texture_assets.texture_coin.clone(),
+            sprite: Sprite {
+                color: GHOST_COLOR,
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, -0.5)),
+            ..Default::default()
+        },
+        Tween::new(
+            TweenTarget::SpriteColor(GHOST_COLOR.with_a(0.0), GHOST_COLOR),
+            GHOST_FADE_IN_SECONDS,
+            Ease::Linear,
+        ),
+        Ghost,
+        player,
+        player.render_layers(),
+        StateOwner(AppState::Ingame),
+    ));
+}
+
+fn record_ghost_run(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut run: ResMut<GhostRun>,
+    coin_query: Query<(&Coin, &Transform)>,
+) {
+    let (coin, transform) = match coin_query.get_single() {
+        Ok(coin) => coin,
+        Err(_) => return,
+    };
+
+    let delta_seconds = time_scale.delta_seconds(&time);
+    run.elapsed += delta_seconds;
+    run.since_last_sample += delta_seconds;
+
+    if run.samples.is_empty() || run.since_last_sample >= GHOST_SAMPLE_INTERVAL {
+        run.since_last_sample = 0.0;
+
+        let elapsed = run.elapsed;
+        run.samples.push(GhostSample {
+            time: elapsed,
+            altitude: coin.altitude,
+            x: transform.translation.x,
+        });
+    }
+}
+
+/// Places the ghost where the coin would see it, the same way `RelativeCoinY` scrolls the world:
+/// anything `n` units above the coin's altitude is drawn `n` units above the coin.
+fn update_ghost(
+    best_ghost: Res<BestGhost>,
+    mut run: ResMut<GhostRun>,
+    coin_query: Query<(&Coin, &Transform), Without<Ghost>>,
+    mut ghost_query: Query<&mut Transform, With<Ghost>>,
+) {
+    let (coin, coin_transform) = match coin_query.get_single() {
+        Ok(coin) => coin,
+        Err(_) => return,
+    };
+    let sample = match best_ghost.sample_at(run.elapsed) {
+        Some(sample) => sample,
+        None => return,
+    };
+
+    run.delta = Some((coin.altitude - sample.altitude) / 10.0);
+
+    ghost_query.for_each_mut(|mut transform| {
+        transform.translation.x = sample.x;
+        transform.translation.y = coin_transform.translation.y + sample.altitude - coin.altitude;
+    });
+}
+
+/// The ghost is the highest flight, the score also counts modifiers and style points.
+fn save_best_ghost(scores: Res<Scores>, run: Res<GhostRun>, mut best_ghost: ResMut<BestGhost>) {
+    if scores.new_altitude_record {
+        best_ghost.0 = run.samples.clone();
+        best_ghost.save();
+    }
+}
@@ -1,9 +1,21 @@
 use bevy::prelude::*;
 
 use crate::{
-    app_state::{AppState, StateOwner},
-    coin::Coin,
-    game_assets::FontAssets,
+    app_state::{AppState, FlightPhase, StateOwner},
+    coin::{Coin, COIN_LOSE_SPEED},
+    danger::danger_level,
+    display::PlayfieldUi,
+    game_assets::{FontAssets, TextureAssets},
+    game_mode::GameMode,
+    ghost::{BestGhost, GhostRun},
+    level::LEVEL_TIER_ALTITUDES,
+    locale::Strings,
+    math::lerp_color,
+    player::{for_player, key_name, PlayerControls, PlayerId},
+    scores::Scores,
+    scoring::RunScore,
+    theme::Theme,
+    time_attack::TimeAttackRun,
 };
 
 pub struct IngameUiPlugin;
@@ -11,166 +23,790 @@ pub struct IngameUiPlugin;
 impl Plugin for IngameUiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(AppState::Ingame), setup_ingame_ui)
+            .add_systems(OnEnter(FlightPhase::Landing), hide_ingame_ui)
             .add_systems(
                 Update,
                 (
                     update_speed_ui,
+                    update_speed_gauge,
+                    update_boost_icons,
                     update_additional_boosts_ui,
-                    update_highest_altitude_ui,
+                    update_altitude_meter,
                     update_altitude_ui,
+                    update_combo_ui,
+                    update_time_attack_ui,
+                    update_ghost_delta_ui,
+                    update_danger_pulse,
+                    update_danger_meter,
+                    update_danger_meter_fill,
+                    update_use_boost_ui,
                 )
                     .run_if(in_state(AppState::Ingame)),
             );
     }
 }
 
+/// Root of the HUD, which is hidden once the flight is over.
+#[derive(Component)]
+struct IngameUi;
+
 #[derive(Component)]
 pub struct SpeedUi;
 
+#[derive(Component)]
+struct SpeedGaugeFill;
+
+#[derive(Component)]
+struct BoostIconUi(usize);
+
+/// Boost count, only shown when there are more boosts than icons.
 #[derive(Component)]
 pub struct AdditionalBoostsUi;
 
+/// Something placed on the altitude meter by its altitude (in metres).
 #[derive(Component)]
-pub struct HighestAltitudeUi;
+enum MeterMarker {
+    Coin,
+    Highest,
+    Best,
+    Tier(f32),
+}
 
+/// Altitude next to the coin marker of the meter.
 #[derive(Component)]
 pub struct AltitudeUi;
 
-pub const INGAME_TEXT_COLOR: Color = Color::GREEN;
+#[derive(Component)]
+pub struct ComboUi;
 
-fn setup_ingame_ui(mut commands: Commands, font_assets: Res<FontAssets>) {
-    commands.spawn((
-        TextBundle::from_section(
-            "Speed: 0",
-            TextStyle {
-                font: font_assets.font_fira.clone(),
-                font_size: 32.0,
-                color: INGAME_TEXT_COLOR,
-                ..Default::default()
-            },
-        )
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: Val::Px(96.0),
-            left: Val::Px(0.0),
-            ..Default::default()
-        }),
-        SpeedUi,
-        StateOwner(AppState::Ingame),
-    ));
-
-    commands.spawn((
-        TextBundle::from_section(
-            "Additional Boosts: 0",
-            TextStyle {
-                font: font_assets.font_fira.clone(),
-                font_size: 32.0,
-                color: INGAME_TEXT_COLOR,
-                ..Default::default()
-            },
-        )
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: Val::Px(96.0 + 32.0),
-            left: Val::Px(0.0),
-            ..Default::default()
-        }),
-        AdditionalBoostsUi,
-        StateOwner(AppState::Ingame),
-    ));
-    commands.spawn((
-        TextBundle::from_section(
-            "[SPACE]: Use boost",
-            TextStyle {
-                font: font_assets.font_fira.clone(),
-                font_size: 32.0,
-                color: Color::WHITE,
-                ..Default::default()
-            },
-        )
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: Val::Px(96.0 + 64.0),
-            left: Val::Px(0.0),
-            ..Default::default()
-        }),
-        StateOwner(AppState::Ingame),
-    ));
-
-    commands.spawn((
-        TextBundle::from_section(
-            "Highest Altitude: 0m",
-            TextStyle {
-                font: font_assets.font_fira.clone(),
-                font_size: 32.0,
-                color: INGAME_TEXT_COLOR,
-                ..Default::default()
-            },
-        )
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: Val::Px(0.0),
-            left: Val::Px(0.0),
-            ..Default::default()
-        }),
-        HighestAltitudeUi,
-        StateOwner(AppState::Ingame),
-    ));
-
-    commands.spawn((
-        TextBundle::from_section(
-            "Altitude: 0.0m",
-            TextStyle {
-                font: font_assets.font_fira.clone(),
-                font_size: 32.0,
-                color: INGAME_TEXT_COLOR,
+/// Frame around the player's view that pulses while the coin is about to fall out.
+#[derive(Component)]
+struct DangerPulseUi;
+
+/// Shows how much falling is left before the coin is out, hidden while it is safe.
+#[derive(Component)]
+struct DangerMeterUi;
+
+#[derive(Component)]
+struct DangerMeterFill;
+
+/// Flashes while a boost could still save the coin.
+#[derive(Component)]
+struct UseBoostUi;
+
+#[derive(Component)]
+pub struct TimeAttackUi;
+
+#[derive(Component)]
+pub struct GhostDeltaUi;
+
+/// Top speed shown by the speed gauge, the launch can't go much faster.
+const SPEED_GAUGE_MAX_SPEED: f32 = 1600.0;
+const SPEED_GAUGE_WIDTH: f32 = 200.0;
+
+/// More boosts than this are shown as one icon and a count.
+const BOOST_ICON_COUNT: usize = 8;
+const BOOST_ICON_SIZE: f32 = 24.0;
+
+/// The meter always reaches a bit past the last tier, so the tiers are spread out.
+const ALTITUDE_METER_MIN_TOP: f32 = 2500.0;
+/// Room left above the highest marker, in parts of its altitude.
+const ALTITUDE_METER_HEADROOM: f32 = 0.1;
+
+const DANGER_PULSE_HZ: f32 = 2.5;
+const DANGER_FRAME_WIDTH: f32 = 12.0;
+const DANGER_METER_WIDTH: f32 = 240.0;
+
+fn speed_gauge_percent(speed: f32) -> f32 {
+    ((speed - COIN_LOSE_SPEED) / (SPEED_GAUGE_MAX_SPEED - COIN_LOSE_SPEED)).clamp(0.0, 1.0) * 100.0
+}
+
+/// How close a falling coin is to being out, from 0 when it stops rising to 1 when it is out.
+fn speed_danger(speed: f32) -> f32 {
+    (speed / COIN_LOSE_SPEED).clamp(0.0, 1.0)
+}
+
+fn setup_ingame_ui(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    texture_assets: Res<TextureAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    game_mode: Res<GameMode>,
+    best_ghost: Res<BestGhost>,
+) {
+    let player_count = game_mode.player_count();
+
+    let playfield = commands
+        .spawn((
+            NodeBundle::default(),
+            PlayfieldUi,
+            IngameUi,
+            StateOwner(AppState::Ingame),
+        ))
+        .id();
+
+    PlayerId::all(player_count).for_each(|player| {
+        let controls = PlayerControls::for_player(player, player_count);
+
+        commands
+            .spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    left: Val::Percent(100.0 * player.0 as f32 / player_count as f32),
+                    width: Val::Percent(100.0 / player_count as f32),
+                    height: Val::Percent(100.0),
+                    ..Default::default()
+                },
                 ..Default::default()
-            },
-        )
-        .with_style(Style {
-            position_type: PositionType::Absolute,
-            top: Val::Px(32.0),
-            left: Val::Px(0.0),
-            ..Default::default()
-        }),
-        AltitudeUi,
-        StateOwner(AppState::Ingame),
-    ));
-}
-
-fn update_speed_ui(coin_query: Query<&Coin>, mut query: Query<&mut Text, With<SpeedUi>>) {
-    let coin = coin_query.single();
-    query.for_each_mut(|mut text| {
-        text.sections[0].value = format!("Speed: {:.2}m/s", coin.speed / 10.0);
+            })
+            .set_parent(playfield)
+            .with_children(|parent| {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            border: UiRect::all(Val::Px(DANGER_FRAME_WIDTH)),
+                            ..Default::default()
+                        },
+                        border_color: Color::NONE.into(),
+                        ..Default::default()
+                    },
+                    DangerPulseUi,
+                    player,
+                ));
+
+                parent.spawn((
+                    TextBundle::from_section(
+                        "",
+                        theme.text_style(&font_assets.font_fira, 24.0, theme.colors.positive),
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(8.0),
+                        left: Val::Px(16.0),
+                        ..Default::default()
+                    }),
+                    SpeedUi,
+                    player,
+                ));
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(40.0),
+                            left: Val::Px(16.0),
+                            width: Val::Px(SPEED_GAUGE_WIDTH),
+                            height: Val::Px(14.0),
+                            ..Default::default()
+                        },
+                        background_color: theme.colors.muted.with_a(0.5).into(),
+                        ..Default::default()
+                    })
+                    .with_children(|gauge| {
+                        gauge.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    width: Val::Percent(0.0),
+                                    height: Val::Percent(100.0),
+                                    ..Default::default()
+                                },
+                                background_color: theme.colors.positive.into(),
+                                ..Default::default()
+                            },
+                            SpeedGaugeFill,
+                            player,
+                        ));
+
+                        // below this tick the coin is falling
+                        gauge.spawn(NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                left: Val::Percent(speed_gauge_percent(0.0)),
+                                width: Val::Px(2.0),
+                                height: Val::Percent(100.0),
+                                ..Default::default()
+                            },
+                            background_color: theme.colors.text.into(),
+                            ..Default::default()
+                        });
+                    });
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(64.0),
+                            left: Val::Px(16.0),
+                            column_gap: Val::Px(4.0),
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|boosts| {
+                        (0..BOOST_ICON_COUNT).for_each(|index| {
+                            boosts.spawn((
+                                ImageBundle {
+                                    style: Style {
+                                        width: Val::Px(BOOST_ICON_SIZE),
+                                        height: Val::Px(BOOST_ICON_SIZE),
+                                        display: Display::None,
+                                        ..Default::default()
+                                    },
+                                    image: UiImage::new(texture_assets.texture_boost.clone()),
+                                    ..Default::default()
+                                },
+                                BoostIconUi(index),
+                                player,
+                            ));
+                        });
+
+                        boosts.spawn((
+                            TextBundle::from_section(
+                                "",
+                                theme.text_style(
+                                    &font_assets.font_fira,
+                                    24.0,
+                                    theme.colors.positive,
+                                ),
+                            ),
+                            AdditionalBoostsUi,
+                            player,
+                        ));
+                    });
+
+                parent.spawn((
+                    TextBundle::from_section(
+                        strings.format("ingame.use_boost", &[("key", &key_name(controls.boost))]),
+                        theme.text_style(&font_assets.font_fira, 20.0, theme.colors.text),
+                    )
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(64.0 + BOOST_ICON_SIZE + 8.0),
+                        left: Val::Px(16.0),
+                        ..Default::default()
+                    }),
+                    UseBoostUi,
+                    player,
+                ));
+
+                parent
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                bottom: Val::Px(96.0),
+                                width: Val::Percent(100.0),
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                row_gap: Val::Px(4.0),
+                                display: Display::None,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        DangerMeterUi,
+                        player,
+                    ))
+                    .with_children(|meter| {
+                        meter.spawn(TextBundle::from_section(
+                            strings.get("danger.falling"),
+                            theme.text_style(&font_assets.font_fira, 28.0, theme.colors.danger),
+                        ));
+
+                        meter
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    width: Val::Px(DANGER_METER_WIDTH),
+                                    height: Val::Px(12.0),
+                                    ..Default::default()
+                                },
+                                background_color: theme.colors.muted.with_a(0.5).into(),
+                                ..Default::default()
+                            })
+                            .with_children(|track| {
+                                track.spawn((
+                                    NodeBundle {
+                                        style: Style {
+                                            width: Val::Percent(100.0),
+                                            height: Val::Percent(100.0),
+                                            ..Default::default()
+                                        },
+                                        background_color: theme.colors.danger.into(),
+                                        ..Default::default()
+                                    },
+                                    DangerMeterFill,
+                                    player,
+                                ));
+                            });
+                    });
+
+                parent.spawn((
+                    TextBundle::from_sections([
+                        TextSection::new(
+                            "",
+                            theme.text_style(&font_assets.font_fira, 32.0, theme.colors.accent),
+                        ),
+                        TextSection::new(
+                            "",
+                            theme.text_style(&font_assets.font_fira, 24.0, theme.colors.positive),
+                        ),
+                    ])
+                    .with_style(Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(136.0),
+                        left: Val::Px(16.0),
+                        ..Default::default()
+                    }),
+                    ComboUi,
+                    player,
+                ));
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(120.0),
+                            bottom: Val::Px(64.0),
+                            right: Val::Px(32.0),
+                            width: Val::Px(8.0),
+                            ..Default::default()
+                        },
+                        background_color: theme.colors.muted.with_a(0.5).into(),
+                        ..Default::default()
+                    })
+                    .with_children(|meter| {
+                        let mut marker = |kind: MeterMarker, width: f32, color: Color, label| {
+                            meter
+                                .spawn((
+                                    NodeBundle {
+                                        style: Style {
+                                            position_type: PositionType::Absolute,
+                                            left: Val::Px(4.0 - width * 0.5),
+                                            width: Val::Px(width),
+                                            height: Val::Px(2.0),
+                                            ..Default::default()
+                                        },
+                                        background_color: color.into(),
+                                        ..Default::default()
+                                    },
+                                    kind,
+                                    player,
+                                ))
+                                .with_children(|marker| {
+                                    marker.spawn(
+                                        TextBundle::from_section(
+                                            label,
+                                            theme.text_style(&font_assets.font_fira, 16.0, color),
+                                        )
+                                        .with_style(
+                                            Style {
+                                                position_type: PositionType::Absolute,
+                                                right: Val::Px(width + 4.0),
+                                                bottom: Val::Px(-10.0),
+                                                ..Default::default()
+                                            },
+                                        ),
+                                    );
+                                });
+                        };
+
+                        LEVEL_TIER_ALTITUDES.iter().for_each(|tier| {
+                            marker(
+                                MeterMarker::Tier(*tier),
+                                16.0,
+                                theme.colors.muted,
+                                strings.format("ingame.meter_altitude", &[("altitude", tier)]),
+                            );
+                        });
+                        marker(
+                            MeterMarker::Best,
+                            24.0,
+                            theme.colors.accent,
+                            strings.get("ingame.meter_best").to_string(),
+                        );
+                        marker(
+                            MeterMarker::Highest,
+                            16.0,
+                            theme.colors.positive,
+                            String::new(),
+                        );
+
+                        meter
+                            .spawn((
+                                ImageBundle {
+                                    style: Style {
+                                        position_type: PositionType::Absolute,
+                                        left: Val::Px(-6.0),
+                                        width: Val::Px(20.0),
+                                        height: Val::Px(20.0),
+                                        margin: UiRect::bottom(Val::Px(-10.0)),
+                                        ..Default::default()
+                                    },
+                                    image: UiImage::new(texture_assets.texture_coin.clone()),
+                                    ..Default::default()
+                                },
+                                MeterMarker::Coin,
+                                player,
+                            ))
+                            .with_children(|marker| {
+                                marker.spawn((
+                                    TextBundle::from_section(
+                                        "",
+                                        theme.text_style(
+                                            &font_assets.font_fira,
+                                            20.0,
+                                            theme.colors.positive,
+                                        ),
+                                    )
+                                    .with_style(Style {
+                                        position_type: PositionType::Absolute,
+                                        right: Val::Px(24.0),
+                                        ..Default::default()
+                                    }),
+                                    AltitudeUi,
+                                    player,
+                                ));
+                            });
+                    });
+
+                if player_count > 1 {
+                    parent.spawn(
+                        TextBundle::from_section(
+                            format!("{}\n{}", player.name(&strings), controls.describe(&strings)),
+                            theme.text_style(&font_assets.font_fira, 20.0, theme.colors.text),
+                        )
+                        .with_style(Style {
+                            position_type: PositionType::Absolute,
+                            bottom: Val::Px(8.0),
+                            left: Val::Px(8.0),
+                            ..Default::default()
+                        }),
+                    );
+                }
+            });
+    });
+
+    if let GameMode::TimeAttack(goal) = *game_mode {
+        commands
+            .spawn((
+                TextBundle::from_sections([
+                    TextSection::new(
+                        format!(
+                            "{}\n",
+                            strings.format("ingame.time_attack", &[("goal", &goal)])
+                        ),
+                        theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                    ),
+                    TextSection::new(
+                        "0.00s\n",
+                        theme.text_style(&font_assets.font_fira, 32.0, theme.colors.positive),
+                    ),
+                    TextSection::new(
+                        "",
+                        theme.text_style(&font_assets.font_fira, 24.0, theme.colors.accent),
+                    ),
+                ])
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    ..Default::default()
+                }),
+                TimeAttackUi,
+            ))
+            .set_parent(playfield);
+    }
+
+    if *game_mode == GameMode::Endless && best_ghost.exists() {
+        commands
+            .spawn((
+                TextBundle::from_section(
+                    "",
+                    theme.text_style(&font_assets.font_fira, 32.0, theme.colors.positive),
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    ..Default::default()
+                }),
+                GhostDeltaUi,
+            ))
+            .set_parent(playfield);
+    }
+}
+
+fn hide_ingame_ui(mut query: Query<&mut Visibility, With<IngameUi>>) {
+    query.for_each_mut(|mut visibility| {
+        *visibility = Visibility::Hidden;
+    });
+}
+
+fn update_speed_ui(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    mut query: Query<(&mut Text, &PlayerId), With<SpeedUi>>,
+) {
+    query.for_each_mut(|(mut text, player)| {
+        if let Some(coin) = for_player(coin_query.iter(), player) {
+            text.sections[0].value = strings.format(
+                "ingame.speed",
+                &[("speed", &format!("{:.2}", coin.speed / 10.0))],
+            );
+            text.sections[0].style.color = lerp_color(
+                theme.colors.positive,
+                theme.colors.danger,
+                speed_danger(coin.speed),
+            );
+        }
+    });
+}
+
+fn update_speed_gauge(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    theme: Res<Theme>,
+    mut query: Query<(&mut Style, &mut BackgroundColor, &PlayerId), With<SpeedGaugeFill>>,
+) {
+    query.for_each_mut(|(mut style, mut background, player)| {
+        if let Some(coin) = for_player(coin_query.iter(), player) {
+            style.width = Val::Percent(speed_gauge_percent(coin.speed));
+            background.0 = lerp_color(
+                theme.colors.positive,
+                theme.colors.danger,
+                speed_danger(coin.speed),
+            );
+        }
+    });
+}
+
+fn update_boost_icons(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    mut query: Query<(&mut Style, &BoostIconUi, &PlayerId)>,
+) {
+    query.for_each_mut(|(mut style, icon, player)| {
+        let coin = match for_player(coin_query.iter(), player) {
+            Some(coin) => coin,
+            None => return,
+        };
+        let count = coin.additional_boosts.max(0) as usize;
+
+        let shown = if count > BOOST_ICON_COUNT {
+            icon.0 == 0
+        } else {
+            icon.0 < count
+        };
+        let display = if shown { Display::Flex } else { Display::None };
+        if style.display != display {
+            style.display = display;
+        }
     });
 }
 
 fn update_additional_boosts_ui(
-    coin_query: Query<&Coin>,
-    mut query: Query<&mut Text, With<AdditionalBoostsUi>>,
+    coin_query: Query<(&Coin, &PlayerId)>,
+    strings: Res<Strings>,
+    mut query: Query<(&mut Text, &PlayerId), With<AdditionalBoostsUi>>,
 ) {
-    let coin = coin_query.single();
-    query.for_each_mut(|mut text| {
-        text.sections[0].value = format!("Boosts Remaining: {}", coin.additional_boosts);
+    query.for_each_mut(|(mut text, player)| {
+        if let Some(coin) = for_player(coin_query.iter(), player) {
+            text.sections[0].value = if coin.additional_boosts as usize > BOOST_ICON_COUNT {
+                strings.plural(
+                    "ingame.boosts_remaining",
+                    coin.additional_boosts as i64,
+                    &[],
+                )
+            } else {
+                String::new()
+            };
+        }
+    });
+}
+
+fn update_altitude_meter(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    scores: Res<Scores>,
+    mut query: Query<(&mut Style, &MeterMarker, &PlayerId)>,
+) {
+    let best = scores.best_altitude as f32;
+
+    query.for_each_mut(|(mut style, marker, player)| {
+        let coin = match for_player(coin_query.iter(), player) {
+            Some(coin) => coin,
+            None => return,
+        };
+        let highest = coin.highest_altitude_recorded / 10.0;
+        let top = ALTITUDE_METER_MIN_TOP.max(best.max(highest) * (1.0 + ALTITUDE_METER_HEADROOM));
+
+        let altitude = match marker {
+            MeterMarker::Coin => coin.altitude / 10.0,
+            MeterMarker::Highest => highest,
+            MeterMarker::Best => best,
+            MeterMarker::Tier(altitude) => *altitude,
+        };
+        style.bottom = Val::Percent((altitude / top).clamp(0.0, 1.0) * 100.0);
+
+        // nothing to beat yet on the first flight
+        if matches!(marker, MeterMarker::Best) && best <= 0.0 {
+            style.display = Display::None;
+        }
+    });
+}
+
+fn update_altitude_ui(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    strings: Res<Strings>,
+    mut query: Query<(&mut Text, &PlayerId), With<AltitudeUi>>,
+) {
+    query.for_each_mut(|(mut text, player)| {
+        if let Some(coin) = for_player(coin_query.iter(), player) {
+            text.sections[0].value = strings.format(
+                "ingame.meter_altitude",
+                &[("altitude", &((coin.altitude / 10.0).floor() as i32))],
+            );
+        }
+    });
+}
+
+fn update_combo_ui(
+    run_score_query: Query<(&RunScore, &PlayerId)>,
+    strings: Res<Strings>,
+    mut query: Query<(&mut Text, &PlayerId), With<ComboUi>>,
+) {
+    query.for_each_mut(|(mut text, player)| {
+        if let Some(run_score) = for_player(run_score_query.iter(), player) {
+            text.sections[0].value = strings.format(
+                "ingame.combo",
+                &[("multiplier", &format!("{:.2}", run_score.multiplier()))],
+            );
+            text.sections[1].value = format!(
+                "\n{}",
+                strings.format(
+                    "ingame.style",
+                    &[("points", &(run_score.points.floor() as i32))]
+                )
+            );
+        }
     });
 }
 
-fn update_highest_altitude_ui(
-    coin_query: Query<&Coin>,
-    mut query: Query<&mut Text, With<HighestAltitudeUi>>,
+fn update_time_attack_ui(
+    run: Res<TimeAttackRun>,
+    strings: Res<Strings>,
+    mut query: Query<&mut Text, With<TimeAttackUi>>,
 ) {
-    let coin = coin_query.single();
     query.for_each_mut(|mut text| {
-        text.sections[0].value = format!(
-            "Highest Altitude: {}m",
-            (coin.highest_altitude_recorded.floor() as i32) / 10
-        );
+        text.sections[1].value = format!("{:.2}s\n", run.elapsed);
+        text.sections[2].value = run
+            .splits
+            .len()
+            .checked_sub(1)
+            .and_then(|index| run.describe_split(index, &strings))
+            .unwrap_or_default();
     });
 }
 
-fn update_altitude_ui(coin_query: Query<&Coin>, mut query: Query<&mut Text, With<AltitudeUi>>) {
-    let coin = coin_query.single();
+fn update_ghost_delta_ui(
+    run: Res<GhostRun>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    mut query: Query<&mut Text, With<GhostDeltaUi>>,
+) {
+    let delta = match run.delta {
+        Some(delta) => delta.round() as i32,
+        None => return,
+    };
+
     query.for_each_mut(|mut text| {
-        text.sections[0].value = format!("Altitude: {:.3}m", coin.altitude / 10.0);
+        text.sections[0].value = if delta >= 0 {
+            strings.format("ingame.ghost_ahead", &[("delta", &format!("{:+}", delta))])
+        } else {
+            strings.format("ingame.ghost_behind", &[("delta", &delta)])
+        };
+        text.sections[0].style.color = if delta >= 0 {
+            theme.colors.positive
+        } else {
+            theme.colors.danger
+        };
+    });
+}
+
+/// 0 to 1 and back, `DANGER_PULSE_HZ` times a second, or steadily 1 with reduced motion.
+fn danger_pulse(time: &Time, theme: &Theme) -> f32 {
+    if theme.reduced_motion {
+        1.0
+    } else {
+        0.5 - 0.5 * (std::f32::consts::TAU * DANGER_PULSE_HZ * time.elapsed_seconds()).cos()
+    }
+}
+
+fn update_danger_pulse(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    time: Res<Time>,
+    theme: Res<Theme>,
+    mut query: Query<(&mut BorderColor, &PlayerId), With<DangerPulseUi>>,
+) {
+    let pulse = danger_pulse(&time, &theme);
+
+    query.for_each_mut(|(mut border, player)| {
+        let level = for_player(coin_query.iter(), player).map_or(0.0, danger_level);
+        border.0 = theme.colors.danger.with_a(level * pulse);
+    });
+}
+
+fn update_danger_meter(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    mut query: Query<(&mut Style, &PlayerId), With<DangerMeterUi>>,
+) {
+    query.for_each_mut(|(mut style, player)| {
+        let level = for_player(coin_query.iter(), player).map_or(0.0, danger_level);
+
+        let display = if level > 0.0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if style.display != display {
+            style.display = display;
+        }
+    });
+}
+
+fn update_danger_meter_fill(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    mut query: Query<(&mut Style, &PlayerId), With<DangerMeterFill>>,
+) {
+    query.for_each_mut(|(mut style, player)| {
+        let level = for_player(coin_query.iter(), player).map_or(0.0, danger_level);
+        style.width = Val::Percent((1.0 - level) * 100.0);
+    });
+}
+
+fn update_use_boost_ui(
+    coin_query: Query<(&Coin, &PlayerId)>,
+    time: Res<Time>,
+    theme: Res<Theme>,
+    mut query: Query<(&mut Text, &PlayerId), With<UseBoostUi>>,
+) {
+    let pulse = danger_pulse(&time, &theme);
+
+    query.for_each_mut(|(mut text, player)| {
+        let coin = match for_player(coin_query.iter(), player) {
+            Some(coin) => coin,
+            None => return,
+        };
+
+        let can_be_saved = danger_level(coin) > 0.0 && coin.additional_boosts > 0;
+        text.sections[0].style.color = if can_be_saved {
+            lerp_color(theme.colors.text, theme.colors.accent, pulse)
+        } else {
+            theme.colors.text
+        };
     });
 }
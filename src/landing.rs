@@ -0,0 +1,243 @@
+use bevy::prelude::*;
+
+use crate::{
+    anim::{Ease, Tween, TweenTarget},
+    app_state::{AppState, FlightPhase, StateOwner},
+    coin::{Coin, Launcher, COIN_FULL_SIZE},
+    coin_camera::CoinCamera,
+    display::PlayfieldUi,
+    game_assets::FontAssets,
+    locale::Strings,
+    player::{for_player, PlayerId},
+    scores::Scores,
+    sfx::{self, Sfx},
+    theme::Theme,
+    transition::{not_leaving_state, ScreenTransition},
+};
+
+pub struct LandingPlugin;
+
+impl Plugin for LandingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Landing>()
+            .add_systems(
+                OnEnter(FlightPhase::Landing),
+                (start_landing, setup_landing_ui),
+            )
+            .add_systems(
+                Update,
+                (
+                    follow_falling_coins,
+                    catch_coins,
+                    count_up_score,
+                    skip_landing,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Ingame))
+                    .run_if(in_state(FlightPhase::Landing))
+                    .run_if(not_leaving_state),
+            );
+    }
+}
+
+/// Much faster than a coin can fall in flight, so even long falls are over quickly.
+const LANDING_FALL_SPEED: f32 = 2000.0;
+/// Falls from higher up are shortened to this.
+const LANDING_MAX_FALL_SECONDS: f32 = 2.0;
+/// So there is something to see when the flight ended close to the ground.
+const LANDING_MIN_FALL_SECONDS: f32 = 0.5;
+/// Keeps the coin in the upper half of the view, so the launcher is seen coming.
+const LANDING_CAMERA_Y: f32 = -160.0;
+const LANDING_CAMERA_FOLLOW_SPEED: f32 = 4.0;
+
+/// Height of the coin above the launcher's centre when it rests on top of it.
+const LAUNCHER_REST_Y: f32 = 60.0 + COIN_FULL_SIZE.y / 2.0;
+
+const TALLY_SECONDS: f32 = 1.5;
+/// How long the final score stays before the end screen.
+const TALLY_HOLD_SECONDS: f32 = 1.0;
+/// Ignores the confirm key at first, it may still be pressed from boosting.
+const SKIP_DELAY_SECONDS: f32 = 0.5;
+const SKIP_KEY: KeyCode = KeyCode::Space;
+
+#[derive(Resource, Default)]
+struct Landing {
+    elapsed: f32,
+    /// Time since the last coin landed, `None` while any is falling.
+    tally_elapsed: Option<f32>,
+}
+
+/// The coin rests on its launcher.
+#[derive(Component)]
+struct Landed;
+
+#[derive(Component)]
+struct ScoreTallyUi;
+
+fn start_landing(
+    mut commands: Commands,
+    mut landing: ResMut<Landing>,
+    mut coin_query: Query<(&mut Coin, &Transform, &PlayerId, Entity), Without<Launcher>>,
+    mut launcher_query: Query<(&mut Transform, &PlayerId), With<Launcher>>,
+) {
+    *landing = Landing::default();
+
+    coin_query.for_each_mut(|(mut coin, transform, player, entity)| {
+        let mut launcher = match for_player(launcher_query.iter_mut(), player) {
+            Some(launcher) => launcher,
+            None => return,
+        };
+
+        // a long way down is cut short, and a short one made a bit longer
+        let coin_y = transform.translation.y;
+        let rest_y = (launcher.translation.y + LAUNCHER_REST_Y).clamp(
+            coin_y - LANDING_FALL_SPEED * LANDING_MAX_FALL_SECONDS,
+            coin_y - LANDING_FALL_SPEED * LANDING_MIN_FALL_SECONDS,
+        );
+        launcher.translation.y = rest_y - LAUNCHER_REST_Y;
+        coin.speed = -LANDING_FALL_SPEED;
+
+        // drifts over the launcher on the way down
+        let fall_seconds = (coin_y - rest_y) / LANDING_FALL_SPEED;
+        commands.entity(entity).insert(Tween::new(
+            TweenTarget::Translation(
+                transform.translation,
+                Vec3::new(
+                    launcher.translation.x,
+                    transform.translation.y,
+                    transform.translation.z,
+                ),
+            ),
+            fall_seconds,
+            Ease::QuadInOut,
+        ));
+    });
+}
+
+fn setup_landing_ui(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+) {
+    commands
+        .spawn((
+            NodeBundle::default(),
+            PlayfieldUi,
+            StateOwner(AppState::Ingame),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    theme.text_style(&font_assets.font_fira, 56.0, theme.colors.accent),
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(20.0),
+                    width: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    ..Default::default()
+                })
+                .with_text_alignment(TextAlignment::Center),
+                ScoreTallyUi,
+            ));
+
+            parent.spawn(
+                TextBundle::from_section(
+                    strings.get("landing.skip"),
+                    theme.text_style(&font_assets.font_fira, 24.0, theme.colors.positive),
+                )
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(16.0),
+                    right: Val::Px(16.0),
+                    ..Default::default()
+                }),
+            );
+        });
+}
+
+fn follow_falling_coins(
+    time: Res<Time>,
+    coin_query: Query<(&Coin, &Transform, &PlayerId), Without<CoinCamera>>,
+    mut camera_query: Query<(&mut Transform, &PlayerId), With<CoinCamera>>,
+) {
+    let follow = (LANDING_CAMERA_FOLLOW_SPEED * time.delta_seconds()).min(1.0);
+
+    camera_query.for_each_mut(|(mut camera_transform, player)| {
+        let coins = coin_query
+            .iter()
+            .map(|(_, transform, player)| (transform, player));
+        if let Some(coin_transform) = for_player(coins, player) {
+            let target_y = coin_transform.translation.y + LANDING_CAMERA_Y;
+            camera_transform.translation.y += (target_y - camera_transform.translation.y) * follow;
+        }
+    });
+}
+
+/// Stops the fall once the launcher has come up to the coin.
+fn catch_coins(
+    mut commands: Commands,
+    sfx: Res<Sfx>,
+    landed_query: Query<(), With<Landed>>,
+    mut coin_query: Query<(&mut Coin, &Transform, &PlayerId, Entity), Without<Launcher>>,
+    mut launcher_query: Query<(&mut Transform, &PlayerId), With<Launcher>>,
+) {
+    coin_query.for_each_mut(|(mut coin, transform, player, entity)| {
+        if landed_query.contains(entity) {
+            return;
+        }
+        let mut launcher = match for_player(launcher_query.iter_mut(), player) {
+            Some(launcher) => launcher,
+            None => return,
+        };
+        if launcher.translation.y + LAUNCHER_REST_Y < transform.translation.y {
+            return;
+        }
+
+        launcher.translation.y = transform.translation.y - LAUNCHER_REST_Y;
+        coin.speed = 0.0;
+        commands.entity(entity).insert(Landed);
+        sfx::play(&mut commands, &sfx.clink);
+    });
+}
+
+fn count_up_score(
+    time: Res<Time>,
+    scores: Res<Scores>,
+    strings: Res<Strings>,
+    falling_query: Query<(), (With<Coin>, Without<Landed>)>,
+    mut landing: ResMut<Landing>,
+    mut transition: ResMut<ScreenTransition>,
+    mut query: Query<&mut Text, With<ScoreTallyUi>>,
+) {
+    if !falling_query.is_empty() {
+        return;
+    }
+
+    let elapsed = landing.tally_elapsed.unwrap_or_default() + time.delta_seconds();
+    landing.tally_elapsed = Some(elapsed);
+
+    let shown = Ease::QuadOut.apply((elapsed / TALLY_SECONDS).min(1.0)) * scores.end_score as f32;
+    query.for_each_mut(|mut text| {
+        text.sections[0].value = strings.format("landing.score", &[("score", &(shown as i32))]);
+    });
+
+    if elapsed >= TALLY_SECONDS + TALLY_HOLD_SECONDS {
+        transition.go_to(AppState::End);
+    }
+}
+
+fn skip_landing(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut landing: ResMut<Landing>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    landing.elapsed += time.delta_seconds();
+
+    if landing.elapsed >= SKIP_DELAY_SECONDS && keyboard_input.just_pressed(SKIP_KEY) {
+        transition.go_to(AppState::End);
+    }
+}
@@ -3,28 +3,35 @@ use rand::prelude::*;
 
 use crate::{
     app_state::{AppState, StateOwner},
-    boost_item::InitBoostItem,
-    cloud::InitCloud,
+    boost_item, cloud,
     coin::Coin,
     coin_camera::{COIN_SCREEN_BOUNDS_X, COIN_SCREEN_BOUNDS_Y},
-    fairy::InitFairy,
+    fairy,
+    game_mode::GameMode,
     math::lerp,
+    modifiers::{ActiveModifiers, Modifier},
+    physics::CULLING_BAND_HEIGHT,
+    player::{for_player, PlayerId},
+    pool::{PoolKind, PoolSpawner},
 };
 
 pub struct LevelPlugin;
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(LevelMetadata::default())
-            .add_systems(OnEnter(AppState::Ingame), reset_level_metadata)
+        app.insert_resource(LevelSeed(0))
+            .add_systems(OnEnter(AppState::Ingame), setup_player_levels)
             .add_systems(
                 Update,
-                (spawn_clouds, spawn_boost, spawn_fairy).run_if(in_state(AppState::Ingame)),
+                // chained, so the seeded rngs are always drawn from in the same order
+                (spawn_clouds, spawn_boost, spawn_fairy)
+                    .chain()
+                    .run_if(in_state(AppState::Ingame)),
             );
     }
 }
 
-#[derive(Resource)]
+#[derive(Component)]
 struct LevelMetadata {
     next_cloud_spawn_altitude: f32,
     next_boost_spawn_altitude: f32,
@@ -41,112 +48,199 @@ impl Default for LevelMetadata {
     }
 }
 
-fn reset_level_metadata(mut level_metadata: ResMut<LevelMetadata>) {
-    *level_metadata = Default::default();
+/// Seed of the level layout, runs with the same seed get the same spawns.
+#[derive(Resource)]
+pub struct LevelSeed(pub u64);
+
+#[derive(Component)]
+struct LevelRng(StdRng);
+
+/// Every player progresses through their own copy of the level, seeded the
+/// same way, so racing players face the same layout.
+fn setup_player_levels(
+    mut commands: Commands,
+    level_seed: Res<LevelSeed>,
+    game_mode: Res<GameMode>,
+) {
+    // without level metadata nothing gets spawned, the tutorial has its own layout
+    if *game_mode == GameMode::Tutorial {
+        return;
+    }
+
+    PlayerId::all(game_mode.player_count()).for_each(|player| {
+        commands.spawn((
+            LevelMetadata::default(),
+            LevelRng(StdRng::seed_from_u64(level_seed.0)),
+            player,
+            StateOwner(AppState::Ingame),
+        ));
+    });
 }
 
 const SPAWN_Y_POS: f32 = COIN_SCREEN_BOUNDS_Y * 2.0;
+/// Highest spawns, above the coin.
+pub const SPAWN_MAX_Y: f32 = SPAWN_Y_POS * 3.0;
+
+/// Altitudes (in metres) where the level gets harder.
+pub const LEVEL_TIER_ALTITUDES: [f32; 4] = [250.0, 500.0, 1000.0, 2000.0];
+
+/// Most clouds, boosts and fairies of a batch, for each tier.
+const MAX_CLOUDS: [f32; 5] = [3.0, 6.0, 10.0, 15.0, 20.0];
+const MAX_BOOSTS: [f32; 5] = [4.0, 4.0, 3.0, 2.0, 1.0];
+const MAX_FAIRIES: [f32; 5] = [3.0, 3.0, 2.0, 2.0, 1.0];
+
+/// Most objects of the kind a player's level can have in the culling band at once. They are
+/// spawned in batches while the coin climbs, each spread over the spawn range.
+pub fn max_live_objects(kind: PoolKind) -> usize {
+    let (per_batch, batch_spacing) = match kind {
+        // doubled by `Modifier::DoubleClouds`
+        PoolKind::Cloud => (max_of(MAX_CLOUDS) * 2, COIN_SCREEN_BOUNDS_Y * 2.0),
+        PoolKind::BoostItem => (max_of(MAX_BOOSTS), SPAWN_Y_POS),
+        // one more with `Modifier::NoManualBoosts`
+        PoolKind::Fairy => (max_of(MAX_FAIRIES) + 1, SPAWN_Y_POS),
+    };
+    let climb = CULLING_BAND_HEIGHT + (SPAWN_MAX_Y - SPAWN_Y_POS);
+
+    // one batch more for the rounding, and one for the camera panning the band
+    per_batch * ((climb / batch_spacing) as usize + 2)
+}
 
-fn lvl(alt: f32, values: [f32; 5]) -> f32 {
-    const LEVELS: [f32; 4] = [250.0, 500.0, 1000.0, 2000.0];
+fn max_of(values: [f32; 5]) -> usize {
+    values.iter().copied().fold(0.0, f32::max) as usize
+}
 
-    *LEVELS
+fn lvl(alt: f32, values: [f32; 5]) -> f32 {
+    *LEVEL_TIER_ALTITUDES
         .iter()
         .zip(values.iter())
         .find(|(l, _)| alt < **l * 10.0)
-        .unwrap_or((&0.0, &values.iter().rev().next().unwrap()))
+        .unwrap_or((&0.0, values.last().unwrap()))
         .1
 }
 
 fn spawn_clouds(
-    mut commands: Commands,
-    mut level_metadata: ResMut<LevelMetadata>,
-    coin_query: Query<&Coin>,
+    mut spawner: PoolSpawner,
+    mut level_query: Query<(&mut LevelMetadata, &mut LevelRng, &PlayerId)>,
+    modifiers: Res<ActiveModifiers>,
+    coin_query: Query<(&Coin, &PlayerId)>,
 ) {
-    let coin = coin_query.single();
-    let alt = coin.altitude;
-
-    if level_metadata.next_cloud_spawn_altitude < alt {
-        let mut rng = rand::thread_rng();
-
-        let low = lvl(alt, [1.0, 2.0, 4.0, 5.0, 6.0]) as i32;
-        let high = lvl(alt, [3.0, 6.0, 10.0, 15.0, 20.0]) as i32;
-
-        let num_clouds = rng.gen_range(low..=high);
-
-        (0..num_clouds).into_iter().for_each(|_| {
-            commands.spawn((
-                InitCloud(Vec2::new(
-                    lerp(-COIN_SCREEN_BOUNDS_X, COIN_SCREEN_BOUNDS_X, rng.gen()),
-                    lerp(SPAWN_Y_POS, SPAWN_Y_POS * 3.0, rng.gen()),
-                )),
-                StateOwner(AppState::Ingame),
-            ));
-        });
+    level_query.for_each_mut(|(mut level_metadata, mut level_rng, player)| {
+        let coin = match for_player(coin_query.iter(), player) {
+            Some(coin) => coin,
+            None => return,
+        };
+        let alt = coin.altitude;
+
+        if level_metadata.next_cloud_spawn_altitude < alt {
+            let rng = &mut level_rng.0;
+
+            let low = lvl(alt, [1.0, 2.0, 4.0, 5.0, 6.0]) as i32;
+            let high = lvl(alt, MAX_CLOUDS) as i32;
+
+            let mut num_clouds = rng.gen_range(low..=high);
+            if modifiers.has(Modifier::DoubleClouds) {
+                num_clouds *= 2;
+            }
+
+            (0..num_clouds).for_each(|_| {
+                cloud::spawn_cloud(
+                    &mut spawner,
+                    Vec2::new(
+                        lerp(-COIN_SCREEN_BOUNDS_X, COIN_SCREEN_BOUNDS_X, rng.gen()),
+                        lerp(SPAWN_Y_POS, SPAWN_MAX_Y, rng.gen()),
+                    ),
+                    *player,
+                );
+            });
 
-        level_metadata.next_cloud_spawn_altitude +=
-            COIN_SCREEN_BOUNDS_Y * (2.0 + rng.gen::<f32>() * lvl(alt, [3.0, 3.0, 2.0, 1.0, 0.5]));
-    }
+            level_metadata.next_cloud_spawn_altitude += COIN_SCREEN_BOUNDS_Y
+                * (2.0 + rng.gen::<f32>() * lvl(alt, [3.0, 3.0, 2.0, 1.0, 0.5]));
+        }
+    });
 }
 
 fn spawn_boost(
-    mut commands: Commands,
-    mut level_metadata: ResMut<LevelMetadata>,
-    coin_query: Query<&Coin>,
+    mut spawner: PoolSpawner,
+    mut level_query: Query<(&mut LevelMetadata, &mut LevelRng, &PlayerId)>,
+    modifiers: Res<ActiveModifiers>,
+    coin_query: Query<(&Coin, &PlayerId)>,
 ) {
-    let coin = coin_query.single();
-    let alt = coin.altitude;
-
-    if level_metadata.next_boost_spawn_altitude < alt {
-        let mut rng = rand::thread_rng();
-
-        let low = 1;
-        let high = lvl(alt, [4.0, 4.0, 3.0, 2.0, 1.0]) as i32;
-
-        let num_boosts = rng.gen_range(low..=high);
-
-        (0..num_boosts).into_iter().for_each(|_| {
-            commands.spawn((
-                InitBoostItem(Vec2::new(
-                    lerp(-COIN_SCREEN_BOUNDS_X, COIN_SCREEN_BOUNDS_X, rng.gen()),
-                    lerp(SPAWN_Y_POS, SPAWN_Y_POS * 3.0, rng.gen()),
-                )),
-                StateOwner(AppState::Ingame),
-            ));
-        });
-        level_metadata.next_boost_spawn_altitude += SPAWN_Y_POS * 1.0;
-    }
-}
+    level_query.for_each_mut(|(mut level_metadata, mut level_rng, player)| {
+        let coin = match for_player(coin_query.iter(), player) {
+            Some(coin) => coin,
+            None => return,
+        };
+        let alt = coin.altitude;
 
-fn spawn_fairy(
-    mut commands: Commands,
-    mut level_metadata: ResMut<LevelMetadata>,
-    coin_query: Query<&Coin>,
-) {
-    let coin = coin_query.single();
-    let alt = coin.altitude;
+        if level_metadata.next_boost_spawn_altitude < alt {
+            level_metadata.next_boost_spawn_altitude += SPAWN_Y_POS * 1.0;
 
-    // TODO: Better level design
-    if level_metadata.next_fairy_spawn_altitude < alt {
-        let mut rng = rand::thread_rng();
+            // manual boosts are useless when they cannot be activated
+            if modifiers.has(Modifier::NoManualBoosts) {
+                return;
+            }
 
-        let low = lvl(alt, [1.0, 1.0, 1.0, 0.0, 0.0]) as i32;
-        let high = lvl(alt, [3.0, 3.0, 2.0, 2.0, 1.0]) as i32;
+            let rng = &mut level_rng.0;
 
-        let num_fairy = rng.gen_range(low..=high);
+            let low = 1;
+            let high = lvl(alt, MAX_BOOSTS) as i32;
 
-        if num_fairy > 0 {
-            (0..num_fairy).into_iter().for_each(|_| {
-                commands.spawn((
-                    InitFairy(Vec2::new(
+            let num_boosts = rng.gen_range(low..=high);
+
+            (0..num_boosts).for_each(|_| {
+                boost_item::spawn_boost_item(
+                    &mut spawner,
+                    Vec2::new(
                         lerp(-COIN_SCREEN_BOUNDS_X, COIN_SCREEN_BOUNDS_X, rng.gen()),
-                        lerp(SPAWN_Y_POS, SPAWN_Y_POS * 3.0, rng.gen()),
-                    )),
-                    StateOwner(AppState::Ingame),
-                ));
+                        lerp(SPAWN_Y_POS, SPAWN_MAX_Y, rng.gen()),
+                    ),
+                    *player,
+                );
             });
         }
+    });
+}
 
-        level_metadata.next_fairy_spawn_altitude += SPAWN_Y_POS * 1.0;
-    }
+fn spawn_fairy(
+    mut spawner: PoolSpawner,
+    mut level_query: Query<(&mut LevelMetadata, &mut LevelRng, &PlayerId)>,
+    modifiers: Res<ActiveModifiers>,
+    coin_query: Query<(&Coin, &PlayerId)>,
+) {
+    level_query.for_each_mut(|(mut level_metadata, mut level_rng, player)| {
+        let coin = match for_player(coin_query.iter(), player) {
+            Some(coin) => coin,
+            None => return,
+        };
+        let alt = coin.altitude;
+
+        // TODO: Better level design
+        if level_metadata.next_fairy_spawn_altitude < alt {
+            let rng = &mut level_rng.0;
+
+            let low = lvl(alt, [1.0, 1.0, 1.0, 0.0, 0.0]) as i32;
+            let high = lvl(alt, MAX_FAIRIES) as i32;
+
+            let mut num_fairy = rng.gen_range(low..=high);
+            // fairies are the only way to gain speed without manual boosts
+            if modifiers.has(Modifier::NoManualBoosts) {
+                num_fairy += 1;
+            }
+
+            if num_fairy > 0 {
+                (0..num_fairy).for_each(|_| {
+                    fairy::spawn_fairy(
+                        &mut spawner,
+                        Vec2::new(
+                            lerp(-COIN_SCREEN_BOUNDS_X, COIN_SCREEN_BOUNDS_X, rng.gen()),
+                            lerp(SPAWN_Y_POS, SPAWN_MAX_Y, rng.gen()),
+                        ),
+                        *player,
+                    );
+                });
+            }
+
+            level_metadata.next_fairy_spawn_altitude += SPAWN_Y_POS * 1.0;
+        }
+    });
 }
@@ -1,3 +1,5 @@
+mod achievements;
+mod achievements_ui;
 mod anim;
 mod app_state;
 mod base;
@@ -6,17 +8,42 @@ mod cloud;
 mod coin;
 mod coin_camera;
 mod coin_launch_ui;
+mod cosmetics;
+mod danger;
+mod display;
+mod edge_markers;
 mod end_ui;
 mod fairy;
 mod game_assets;
+mod game_mode;
+mod ghost;
 mod ingame_ui;
+mod landing;
 mod level;
 mod loading_ui;
+mod locale;
 mod main_menu_ui;
 mod math;
+mod modifiers;
 mod physics;
+mod player;
+mod pool;
+mod rescue;
 mod scores;
+mod scoring;
+mod settings;
+mod settings_ui;
+mod sfx;
+mod shop_ui;
+mod storage;
+mod theme;
+mod time_attack;
+mod transition;
+mod tutorial;
+mod upgrades;
 
+use achievements::AchievementsPlugin;
+use achievements_ui::AchievementsUiPlugin;
 use anim::AnimPlugin;
 use app_state::AppStatePlugin;
 use base::CorePlugin;
@@ -26,22 +53,47 @@ use cloud::CloudPlugin;
 use coin::CoinPlugin;
 use coin_camera::CoinCameraPlugin;
 use coin_launch_ui::CoinLaunchUiPlugin;
+use cosmetics::CosmeticsPlugin;
+use danger::DangerPlugin;
+use display::DisplayPlugin;
+use edge_markers::EdgeMarkersPlugin;
 use end_ui::EndUiPlugin;
 use fairy::FairyPlugin;
 use game_assets::GameAssetsPlugin;
+use game_mode::GameModePlugin;
+use ghost::GhostPlugin;
 use ingame_ui::IngameUiPlugin;
+use landing::LandingPlugin;
 use level::LevelPlugin;
 use loading_ui::LoadingUiPlugin;
+use locale::LocalePlugin;
 use main_menu_ui::MainMenuUiPlugin;
+use modifiers::ModifiersPlugin;
 use physics::PhysicsPlugin;
+use pool::PoolPlugin;
+use rescue::RescuePlugin;
 use scores::ScoresPlugin;
+use scoring::ScoringPlugin;
+use settings::SettingsPlugin;
+use settings_ui::SettingsUiPlugin;
+use sfx::SfxPlugin;
+use shop_ui::ShopUiPlugin;
+use theme::ThemePlugin;
+use time_attack::TimeAttackPlugin;
+use transition::TransitionPlugin;
+use tutorial::TutorialPlugin;
+use upgrades::UpgradesPlugin;
 
 pub fn run() {
     App::new()
         .add_plugins((
             (
                 CorePlugin,
+                DisplayPlugin,
                 AppStatePlugin,
+                SettingsPlugin,
+                ThemePlugin,
+                LocalePlugin,
                 LoadingUiPlugin,
                 GameAssetsPlugin,
                 MainMenuUiPlugin,
@@ -60,6 +112,29 @@ pub fn run() {
                 EndUiPlugin,
                 ScoresPlugin,
             ),
+            (
+                AchievementsPlugin,
+                AchievementsUiPlugin,
+                GameModePlugin,
+                ModifiersPlugin,
+                TimeAttackPlugin,
+                GhostPlugin,
+                ScoringPlugin,
+                UpgradesPlugin,
+                ShopUiPlugin,
+                CosmeticsPlugin,
+                TransitionPlugin,
+                TutorialPlugin,
+                SettingsUiPlugin,
+            ),
+            (
+                SfxPlugin,
+                DangerPlugin,
+                RescuePlugin,
+                LandingPlugin,
+                EdgeMarkersPlugin,
+                PoolPlugin,
+            ),
         ))
         .run();
 }
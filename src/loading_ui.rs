@@ -1,7 +1,12 @@
 use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
 use iyes_progress::ProgressCounter;
 
-use crate::app_state::{AppState, StateOwner};
+use crate::{
+    app_state::{AppState, StateOwner},
+    display::fit_projection,
+    locale::Strings,
+    theme::Theme,
+};
 
 pub struct LoadingUiPlugin;
 
@@ -18,13 +23,13 @@ impl Plugin for LoadingUiPlugin {
 #[derive(Component)]
 struct LoadingTextUi;
 
-fn setup_loading_ui(mut commands: Commands) {
+fn setup_loading_ui(mut commands: Commands, theme: Res<Theme>, strings: Res<Strings>) {
     commands.spawn((
         Camera2dBundle {
             camera_2d: Camera2d {
-                clear_color: ClearColorConfig::Custom(Color::BLACK),
-                ..Default::default()
+                clear_color: ClearColorConfig::Custom(theme.colors.background),
             },
+            projection: fit_projection(),
             ..Default::default()
         },
         StateOwner(AppState::Loading),
@@ -48,10 +53,10 @@ fn setup_loading_ui(mut commands: Commands) {
         .with_children(|parent| {
             parent.spawn((
                 TextBundle::from_section(
-                    "Loading: 0/0",
+                    strings.format("loading.progress", &[("done", &0), ("total", &0)]),
                     TextStyle {
-                        font_size: 48.0,
-                        color: Color::WHITE,
+                        font_size: theme.font_size(48.0),
+                        color: theme.colors.text,
                         ..Default::default()
                     },
                 ),
@@ -62,6 +67,7 @@ fn setup_loading_ui(mut commands: Commands) {
 
 fn update_loading_ui(
     progress: Option<Res<ProgressCounter>>,
+    strings: Res<Strings>,
     mut last_done: Local<u32>,
     mut last_total: Local<u32>,
     mut query: Query<&mut Text, With<LoadingTextUi>>,
@@ -72,7 +78,10 @@ fn update_loading_ui(
             *last_total = progress.total;
 
             query.for_each_mut(|mut text| {
-                text.sections[0].value = format!("Loading: {}/{}", progress.done, progress.total);
+                text.sections[0].value = strings.format(
+                    "loading.progress",
+                    &[("done", &progress.done), ("total", &progress.total)],
+                );
             });
         }
     }
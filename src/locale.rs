@@ -0,0 +1,291 @@
+use std::{collections::HashMap, fmt::Display};
+
+use ab_glyph::Font as _;
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    text::update_text2d_layout,
+    ui::widget::measure_text_system,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    game_assets::{DataAssets, FontAssets},
+    settings::{Setting, SettingChangedEvent, Settings},
+};
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<StringTable>()
+            .init_asset_loader::<StringTableLoader>()
+            .insert_resource(Strings::new())
+            .add_systems(PreUpdate, update_strings)
+            .add_systems(
+                PostUpdate,
+                apply_font_fallback
+                    .run_if(resource_exists::<FontAssets>())
+                    .before(measure_text_system)
+                    .before(update_text2d_layout),
+            );
+    }
+}
+
+/// Picks between the `one` and `other` form of a counted text.
+#[derive(Deserialize, Clone, Copy, Default)]
+enum PluralRule {
+    /// Only 1 is singular, e.g. English and German.
+    #[default]
+    SingularOne,
+    /// 0 and 1 are singular, e.g. French.
+    SingularZeroAndOne,
+    /// The language has no plural forms.
+    Invariant,
+}
+
+impl PluralRule {
+    fn is_singular(&self, count: i64) -> bool {
+        match self {
+            PluralRule::SingularOne => count == 1,
+            PluralRule::SingularZeroAndOne => count == 0 || count == 1,
+            PluralRule::Invariant => false,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+struct PluralForms {
+    /// Can be left out by languages without plural forms.
+    #[serde(default)]
+    one: String,
+    other: String,
+}
+
+/// All texts of one language, loaded from a `*.strings.ron` file.
+///
+/// Texts may contain `{name}` placeholders, which are filled in by `Strings::format`.
+#[derive(Deserialize, TypeUuid, TypePath, Clone)]
+#[uuid = "b3a4f0d2-7c19-4e8b-a6d5-2f9e1c7b4a60"]
+pub struct StringTable {
+    /// ISO 639-1 code, e.g. "en".
+    code: String,
+    /// Name of the language, in the language itself.
+    language: String,
+    #[serde(default)]
+    plural_rule: PluralRule,
+    strings: HashMap<String, String>,
+    #[serde(default)]
+    plurals: HashMap<String, PluralForms>,
+}
+
+#[derive(Default)]
+struct StringTableLoader;
+
+impl AssetLoader for StringTableLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let table = ron::de::from_bytes::<StringTable>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(table));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["strings.ron"]
+    }
+}
+
+/// Compiled in, so there is something to show while the other tables load, and for keys that a
+/// translation misses.
+const FALLBACK_TABLE: &str = include_str!("../assets/data/en.strings.ron");
+
+/// Texts of the selected language, used by all the UI.
+#[derive(Resource)]
+pub struct Strings {
+    table: StringTable,
+    fallback: StringTable,
+    /// Code and name of every loaded language, sorted by code.
+    languages: Vec<(String, String)>,
+}
+
+impl Strings {
+    fn new() -> Self {
+        let fallback: StringTable =
+            ron::from_str(FALLBACK_TABLE).expect("the built-in string table is valid");
+
+        Self {
+            table: fallback.clone(),
+            languages: vec![(fallback.code.clone(), fallback.language.clone())],
+            fallback,
+        }
+    }
+
+    /// Text for `key`, in English if the language misses it, or the key itself if no table has it.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.get_or(key, key)
+    }
+
+    pub fn get_or<'a>(&'a self, key: &str, default: &'a str) -> &'a str {
+        self.table
+            .strings
+            .get(key)
+            .or_else(|| self.fallback.strings.get(key))
+            .map(String::as_str)
+            .unwrap_or(default)
+    }
+
+    /// Text for `key` with its `{name}` placeholders replaced by `args`.
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        fill_placeholders(self.get(key), args)
+    }
+
+    /// Like `format`, but picks the singular or plural text for `count`, which is also available
+    /// as the `{count}` placeholder.
+    pub fn plural(&self, key: &str, count: i64, args: &[(&str, &dyn Display)]) -> String {
+        let (forms, rule) = match self.table.plurals.get(key) {
+            Some(forms) => (forms, self.table.plural_rule),
+            None => match self.fallback.plurals.get(key) {
+                Some(forms) => (forms, self.fallback.plural_rule),
+                None => return key.to_string(),
+            },
+        };
+        let text = if rule.is_singular(count) && !forms.one.is_empty() {
+            &forms.one
+        } else {
+            &forms.other
+        };
+
+        fill_placeholders(&fill_placeholders(text, &[("count", &count)]), args)
+    }
+
+    /// Name of the shown language, in the language itself.
+    pub fn language(&self) -> &str {
+        &self.table.language
+    }
+
+    /// Code of the loaded language after `code`, wrapping around.
+    pub fn next_language(&self, code: &str) -> String {
+        let index = self
+            .languages
+            .iter()
+            .position(|(language_code, _)| language_code == code)
+            .map_or(0, |index| index + 1);
+
+        self.languages[index % self.languages.len()].0.clone()
+    }
+}
+
+fn fill_placeholders(text: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// Run condition for rebuilding a screen after the language changed while it was shown.
+pub fn strings_changed(strings: Res<Strings>) -> bool {
+    strings.is_changed() && !strings.is_added()
+}
+
+fn update_strings(
+    settings: Res<Settings>,
+    data_assets: Option<Res<DataAssets>>,
+    tables: Res<Assets<StringTable>>,
+    mut table_events: EventReader<AssetEvent<StringTable>>,
+    mut setting_changed_events: EventReader<SettingChangedEvent>,
+    mut strings: ResMut<Strings>,
+) {
+    let tables_changed = table_events.iter().count() > 0;
+    let language_changed = setting_changed_events
+        .iter()
+        .any(|event| event.0 == Setting::Language);
+    // the tables are part of the data loaded on startup
+    let data_assets = match data_assets {
+        Some(data_assets) => data_assets,
+        None => return,
+    };
+    if !language_changed && !data_assets.is_added() && !tables_changed {
+        return;
+    }
+
+    let loaded = data_assets
+        .string_tables
+        .iter()
+        .filter_map(|handle| tables.get(handle))
+        .collect::<Vec<_>>();
+    if loaded.is_empty() {
+        return;
+    }
+
+    let mut languages = loaded
+        .iter()
+        .map(|table| (table.code.clone(), table.language.clone()))
+        .collect::<Vec<_>>();
+    languages.sort();
+    strings.languages = languages;
+
+    strings.table = loaded
+        .into_iter()
+        .find(|table| table.code == settings.language)
+        .unwrap_or(&strings.fallback)
+        .clone();
+}
+
+/// Swaps the font of texts with characters their font doesn't have, e.g. accents in a
+/// translation, to the next font in the fallback chain that has all of them.
+fn apply_font_fallback(
+    font_assets: Res<FontAssets>,
+    fonts: Res<Assets<Font>>,
+    mut query: Query<&mut Text, Changed<Text>>,
+) {
+    // the built-in font only has ASCII, FiraSans covers most European languages
+    let chain = [
+        Handle::default(),
+        font_assets.font_fira.clone(),
+        font_assets.font_fallback.clone(),
+    ];
+    let covers = |handle: &Handle<Font>, value: &str| match fonts.get(handle) {
+        Some(font) => value
+            .chars()
+            .filter(|c| !c.is_control())
+            .all(|c| font.font.glyph_id(c).0 != 0),
+        None => false,
+    };
+
+    query.for_each_mut(|mut text| {
+        let better_fonts = text
+            .sections
+            .iter()
+            .map(|section| {
+                let index = chain
+                    .iter()
+                    .position(|handle| *handle == section.style.font)?;
+                if covers(&chain[index], &section.value) {
+                    return None;
+                }
+                chain[index + 1..]
+                    .iter()
+                    .find(|handle| covers(handle, &section.value))
+                    .cloned()
+            })
+            .collect::<Vec<_>>();
+
+        // only touch the text when a font actually changes, so this doesn't rerun every frame
+        if better_fonts.iter().any(Option::is_some) {
+            text.sections
+                .iter_mut()
+                .zip(better_fonts)
+                .for_each(|(section, better)| {
+                    if let Some(better) = better {
+                        section.style.font = better;
+                    }
+                });
+        }
+    });
+}
@@ -1,5 +1,3 @@
-use mini_jam_139_fae_coin;
-
 fn main() {
     mini_jam_139_fae_coin::run();
 }
@@ -1,32 +1,118 @@
-use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+use bevy::{core_pipeline::clear_color::ClearColorConfig, ecs::system::SystemParam, prelude::*};
 
 use crate::{
+    achievements::UnlockedAchievements,
+    anim::{Ease, FrameAnimation, Tween, TweenTarget},
     app_state::{AppState, StateOwner},
+    coin::{COIN_FULL_SIZE, COIN_SPIN_FRAMES},
+    cosmetics::{CoinSkin, SelectedCosmetics, TrailEmitter, TrailStyle},
+    display::fit_projection,
     game_assets::{FontAssets, TextureAssets},
+    game_mode::{today, GameMode},
+    locale::{strings_changed, Strings},
+    scores::Scores,
+    theme::{theme_changed, Theme},
+    time_attack::TIME_ATTACK_GOALS,
+    transition::{not_leaving_state, ScreenTransition},
+    tutorial::TutorialProgress,
 };
 
 pub struct MainMenuUiPlugin;
 
 impl Plugin for MainMenuUiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::MainMenu), setup_main_menu_ui)
-            .add_systems(Update, start_game.run_if(in_state(AppState::MainMenu)));
+        app.add_systems(
+            OnEnter(AppState::MainMenu),
+            (setup_main_menu_ui, setup_settings_hint_ui),
+        )
+        .add_systems(
+            Update,
+            (
+                start_game,
+                skip_tutorial,
+                start_daily_challenge,
+                start_time_attack,
+                start_race,
+                open_achievements,
+                cycle_coin_skin,
+                cycle_trail,
+                update_cosmetics_ui,
+                open_settings,
+            )
+                .run_if(in_state(AppState::MainMenu))
+                .run_if(not_leaving_state),
+        )
+        .add_systems(
+            Update,
+            // the whole menu is respawned, so it picks up the new texts, sizes and colours
+            (
+                clear_main_menu_ui,
+                (setup_main_menu_ui, setup_settings_hint_ui),
+            )
+                .chain()
+                .run_if(in_state(AppState::MainMenu))
+                .run_if(theme_changed.or_else(strings_changed)),
+        );
     }
 }
 
-const BACKGROUND_COLOR: Color = Color::rgb(40.0 / 255.0, 40.0 / 255.0, 63.0 / 255.0);
+#[derive(Component)]
+struct SkinPreview;
+
+const SKIN_PREVIEW_SPIN_FPS: f32 = 12.0;
+
+const ENTRANCE_SECONDS: f32 = 0.4;
+const ENTRANCE_STAGGER_SECONDS: f32 = 0.08;
+const ENTRANCE_SLIDE: f32 = 30.0;
+
+/// Fades and slides a menu line in, `index` lines after the first one.
+fn entrance_tween(index: usize, color: Color) -> Tween {
+    Tween::new(
+        TweenTarget::TextColor(color.with_a(0.0), color),
+        ENTRANCE_SECONDS,
+        Ease::CubicOut,
+    )
+    .and(TweenTarget::UiOffset(
+        Vec2::new(-ENTRANCE_SLIDE, 0.0),
+        Vec2::ZERO,
+    ))
+    .with_delay(index as f32 * ENTRANCE_STAGGER_SECONDS)
+}
+
+#[derive(Component)]
+struct SkinUi;
+
+#[derive(Component)]
+struct TrailUi;
+
+/// What the player has played, unlocked and picked so far.
+#[derive(SystemParam)]
+struct PlayerProfile<'w> {
+    scores: Res<'w, Scores>,
+    cosmetics: Res<'w, SelectedCosmetics>,
+    tutorial: Res<'w, TutorialProgress>,
+}
 
 fn setup_main_menu_ui(
     mut commands: Commands,
     texture_assets: Res<TextureAssets>,
     font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    profile: PlayerProfile,
 ) {
+    let PlayerProfile {
+        scores,
+        cosmetics,
+        tutorial,
+    } = profile;
+
     commands.spawn((
         Camera2dBundle {
             camera_2d: Camera2d {
-                clear_color: ClearColorConfig::Custom(BACKGROUND_COLOR),
-                ..Default::default()
+                clear_color: ClearColorConfig::Custom(theme.colors.background),
             },
+            projection: fit_projection(),
             ..Default::default()
         },
         StateOwner(AppState::MainMenu),
@@ -70,26 +156,22 @@ fn setup_main_menu_ui(
                             background_color: Color::WHITE.into(),
                             ..Default::default()
                         },
-                        UiImage::new(texture_assets.texture_coin.clone()),
+                        UiImage::new(cosmetics.skin.texture(&texture_assets)),
+                        SkinPreview,
                     ));
                     parent.spawn(TextBundle::from_section(
-                        "Coin in the Sky",
+                        strings.get("menu.title"),
                         TextStyle {
-                            font_size: 48.0,
-                            color: Color::CYAN,
+                            font_size: theme.font_size(48.0),
+                            color: theme.colors.title,
                             ..Default::default()
                         },
                     ));
                 });
 
             parent.spawn(TextBundle::from_section(
-                "Flip your coin into the sky, and maintain it as high as possible!",
-                TextStyle {
-                    font: font_assets.font_fira.clone(),
-                    font_size: 28.0,
-                    color: Color::WHITE,
-                    ..Default::default()
-                },
+                strings.get("menu.subtitle"),
+                theme.text_style(&font_assets.font_fira, 28.0, theme.colors.text),
             ));
 
             parent
@@ -102,14 +184,15 @@ fn setup_main_menu_ui(
                     },
                     ..Default::default()
                 })
-                .with_children(|mut parent| {
+                .with_children(|parent| {
                     fn tip_item(
                         parent: &mut ChildBuilder,
                         font_assets: &Res<FontAssets>,
+                        theme: &Theme,
                         image: Handle<Image>,
                         image_width: f32,
                         image_height: f32,
-                        text: String,
+                        text: &str,
                     ) {
                         parent
                             .spawn(NodeBundle {
@@ -136,50 +219,126 @@ fn setup_main_menu_ui(
                                 ));
                                 parent.spawn(TextBundle::from_section(
                                     text,
-                                    TextStyle {
-                                        font: font_assets.font_fira.clone(),
-                                        font_size: 16.0,
-                                        color: Color::WHITE,
-                                        ..Default::default()
-                                    },
+                                    theme.text_style(
+                                        &font_assets.font_fira,
+                                        16.0,
+                                        theme.colors.text,
+                                    ),
                                 ));
                             });
                     }
                     tip_item(
-                        &mut parent,
+                        parent,
                         &font_assets,
+                        &theme,
                         texture_assets.texture_single_cloud.clone(),
                         64.0,
                         32.0,
-                        "Avoid the clouds using the\n[LEFT] and [RIGHT] arrow keys.".to_string(),
+                        strings.get("menu.tip_clouds"),
                     );
                     tip_item(
-                        &mut parent,
+                        parent,
                         &font_assets,
+                        &theme,
                         texture_assets.texture_fairy.clone(),
                         48.0,
                         48.0,
-                        "Touch the fairy to gain\nan automatic boost!".to_string(),
+                        strings.get("menu.tip_fairy"),
                     );
                     tip_item(
-                        &mut parent,
+                        parent,
                         &font_assets,
+                        &theme,
                         texture_assets.texture_boost.clone(),
                         48.0,
                         48.0,
-                        "Collect manual boost\n(use [SPACE] to activate them).".to_string(),
+                        strings.get("menu.tip_boost"),
                     );
                 });
 
-            parent.spawn(TextBundle::from_section(
-                "Press [SPACE] to start",
-                TextStyle {
-                    font: font_assets.font_fira.clone(),
-                    font_size: 32.0,
-                    color: Color::GREEN,
-                    ..Default::default()
-                },
+            parent.spawn((
+                TextBundle::from_section(
+                    strings.get(if tutorial.completed {
+                        "menu.start"
+                    } else {
+                        "menu.start_tutorial"
+                    }),
+                    theme.text_style(&font_assets.font_fira, 32.0, theme.colors.positive),
+                ),
+                entrance_tween(0, theme.colors.positive),
+            ));
+
+            if !tutorial.completed {
+                parent.spawn((
+                    TextBundle::from_section(
+                        strings.get("menu.skip_tutorial"),
+                        theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                    ),
+                    entrance_tween(0, theme.colors.text),
+                ));
+            }
+
+            parent.spawn((
+                TextBundle::from_section(
+                    strings.get(if scores.has_attempted_daily(today()) {
+                        "menu.daily_practice"
+                    } else {
+                        "menu.daily"
+                    }),
+                    theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                ),
+                entrance_tween(1, theme.colors.text),
+            ));
+
+            parent.spawn((
+                TextBundle::from_section(
+                    strings.get("menu.time_attack"),
+                    theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                ),
+                entrance_tween(2, theme.colors.text),
+            ));
+
+            parent.spawn((
+                TextBundle::from_section(
+                    strings.get("menu.race"),
+                    theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                ),
+                entrance_tween(3, theme.colors.text),
+            ));
+
+            parent.spawn((
+                TextBundle::from_section(
+                    strings.get("menu.achievements"),
+                    theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                ),
+                entrance_tween(4, theme.colors.text),
             ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(32.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            theme.text_style(&font_assets.font_fira, 24.0, theme.colors.accent),
+                        ),
+                        SkinUi,
+                    ));
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            theme.text_style(&font_assets.font_fira, 24.0, theme.colors.accent),
+                        ),
+                        TrailUi,
+                    ));
+                });
         });
 
     commands.spawn((
@@ -194,10 +353,236 @@ fn setup_main_menu_ui(
         },
         StateOwner(AppState::MainMenu),
     ));
+
+    commands.spawn((
+        SpriteSheetBundle {
+            texture_atlas: cosmetics.skin.spin_atlas(&texture_assets),
+            sprite: TextureAtlasSprite {
+                custom_size: Some(COIN_FULL_SIZE),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(Vec3::new(0.0, -190.0, 3.0)),
+            ..Default::default()
+        },
+        FrameAnimation::new(
+            COIN_SPIN_FRAMES,
+            if theme.reduced_motion {
+                0.0
+            } else {
+                SKIN_PREVIEW_SPIN_FPS
+            },
+        ),
+        TrailEmitter::new(cosmetics.trail),
+        SkinPreview,
+        StateOwner(AppState::MainMenu),
+    ));
 }
 
-fn start_game(keyboard_input: Res<Input<KeyCode>>, mut next_state: ResMut<NextState<AppState>>) {
+/// The very first flight is the tutorial, until it's completed or skipped.
+fn start_game(
+    keyboard_input: Res<Input<KeyCode>>,
+    tutorial: Res<TutorialProgress>,
+    mut game_mode: ResMut<GameMode>,
+    mut transition: ResMut<ScreenTransition>,
+) {
     if keyboard_input.just_pressed(KeyCode::Space) {
-        next_state.set(AppState::CoinLaunch);
+        *game_mode = if tutorial.completed {
+            GameMode::Endless
+        } else {
+            GameMode::Tutorial
+        };
+        transition.go_to(AppState::CoinLaunch);
+    }
+}
+
+fn skip_tutorial(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut tutorial: ResMut<TutorialProgress>,
+    mut game_mode: ResMut<GameMode>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if !tutorial.completed && keyboard_input.just_pressed(KeyCode::S) {
+        tutorial.complete();
+        *game_mode = GameMode::Endless;
+        transition.go_to(AppState::CoinLaunch);
+    }
+}
+
+fn start_daily_challenge(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut game_mode: ResMut<GameMode>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if keyboard_input.just_pressed(KeyCode::D) {
+        *game_mode = GameMode::Daily;
+        transition.go_to(AppState::CoinLaunch);
+    }
+}
+
+fn start_time_attack(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut game_mode: ResMut<GameMode>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if keyboard_input.just_pressed(KeyCode::T) {
+        *game_mode = GameMode::TimeAttack(TIME_ATTACK_GOALS[0]);
+        transition.go_to(AppState::CoinLaunch);
+    }
+}
+
+fn start_race(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut game_mode: ResMut<GameMode>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if keyboard_input.just_pressed(KeyCode::R) {
+        *game_mode = GameMode::Race;
+        transition.go_to(AppState::CoinLaunch);
+    }
+}
+
+fn open_achievements(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if keyboard_input.just_pressed(KeyCode::A) {
+        transition.go_to(AppState::Achievements);
+    }
+}
+
+/// Picks the next unlocked option after `current`, wrapping around.
+fn next_unlocked<T: Copy + PartialEq>(
+    options: &[T],
+    current: T,
+    is_unlocked: impl Fn(&T) -> bool,
+) -> T {
+    let index = options
+        .iter()
+        .position(|option| *option == current)
+        .unwrap_or(0);
+
+    options
+        .iter()
+        .cycle()
+        .skip(index + 1)
+        .take(options.len())
+        .find(|option| is_unlocked(option))
+        .copied()
+        .unwrap_or(current)
+}
+
+fn cycle_coin_skin(
+    keyboard_input: Res<Input<KeyCode>>,
+    achievements: Res<UnlockedAchievements>,
+    scores: Res<Scores>,
+    texture_assets: Res<TextureAssets>,
+    mut cosmetics: ResMut<SelectedCosmetics>,
+    mut sprite_query: Query<&mut Handle<TextureAtlas>, With<SkinPreview>>,
+    mut image_query: Query<&mut UiImage, With<SkinPreview>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::C) {
+        return;
     }
+
+    cosmetics.skin = next_unlocked(&CoinSkin::ALL, cosmetics.skin, |skin| {
+        skin.unlock().is_unlocked(&achievements, &scores)
+    });
+    cosmetics.save();
+
+    sprite_query.for_each_mut(|mut handle| *handle = cosmetics.skin.spin_atlas(&texture_assets));
+    image_query.for_each_mut(|mut image| image.texture = cosmetics.skin.texture(&texture_assets));
+}
+
+fn cycle_trail(
+    keyboard_input: Res<Input<KeyCode>>,
+    achievements: Res<UnlockedAchievements>,
+    scores: Res<Scores>,
+    mut cosmetics: ResMut<SelectedCosmetics>,
+    mut query: Query<&mut TrailEmitter, With<SkinPreview>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::V) {
+        return;
+    }
+
+    cosmetics.trail = next_unlocked(&TrailStyle::ALL, cosmetics.trail, |trail| {
+        trail.unlock().is_unlocked(&achievements, &scores)
+    });
+    cosmetics.save();
+
+    query.for_each_mut(|mut emitter| emitter.style = cosmetics.trail);
+}
+
+fn update_cosmetics_ui(
+    achievements: Res<UnlockedAchievements>,
+    scores: Res<Scores>,
+    cosmetics: Res<SelectedCosmetics>,
+    strings: Res<Strings>,
+    mut skin_query: Query<&mut Text, (With<SkinUi>, Without<TrailUi>)>,
+    mut trail_query: Query<&mut Text, With<TrailUi>>,
+) {
+    let next_locked_skin = CoinSkin::ALL
+        .iter()
+        .map(|skin| skin.unlock())
+        .find(|unlock| !unlock.is_unlocked(&achievements, &scores));
+    let next_locked_trail = TrailStyle::ALL
+        .iter()
+        .map(|trail| trail.unlock())
+        .find(|unlock| !unlock.is_unlocked(&achievements, &scores));
+
+    skin_query.for_each_mut(|mut text| {
+        let skin = cosmetics.skin.name(&strings);
+        text.sections[0].value = match &next_locked_skin {
+            Some(unlock) => strings.format(
+                "menu.skin_next",
+                &[("skin", &skin), ("unlock", &unlock.describe(&strings))],
+            ),
+            None => strings.format("menu.skin", &[("skin", &skin)]),
+        };
+    });
+
+    trail_query.for_each_mut(|mut text| {
+        let trail = cosmetics.trail.name(&strings);
+        text.sections[0].value = match &next_locked_trail {
+            Some(unlock) => strings.format(
+                "menu.trail_next",
+                &[("trail", &trail), ("unlock", &unlock.describe(&strings))],
+            ),
+            None => strings.format("menu.trail", &[("trail", &trail)]),
+        };
+    });
+}
+
+fn setup_settings_hint_ui(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+) {
+    commands.spawn((
+        TextBundle::from_section(
+            strings.get("menu.settings"),
+            theme.text_style(&font_assets.font_fira, 18.0, theme.colors.muted),
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            left: Val::Px(16.0),
+            bottom: Val::Px(16.0),
+            ..Default::default()
+        }),
+        StateOwner(AppState::MainMenu),
+    ));
+}
+
+fn open_settings(keyboard_input: Res<Input<KeyCode>>, mut transition: ResMut<ScreenTransition>) {
+    if keyboard_input.just_pressed(KeyCode::O) {
+        transition.go_to(AppState::Settings);
+    }
+}
+
+fn clear_main_menu_ui(mut commands: Commands, query: Query<(Entity, &StateOwner)>) {
+    query.for_each(|(entity, owner)| {
+        if owner.0 == AppState::MainMenu {
+            commands.entity(entity).despawn_recursive();
+        }
+    });
 }
@@ -1,3 +1,13 @@
+use bevy::prelude::*;
+
 pub fn lerp(a: f32, b: f32, val: f32) -> f32 {
     a + (b - a) * val
 }
+
+pub fn lerp_color(start: Color, end: Color, t: f32) -> Color {
+    let start = Vec4::from(start.as_rgba_f32());
+    let end = Vec4::from(end.as_rgba_f32());
+    let color = start.lerp(end, t);
+
+    Color::rgba(color.x, color.y, color.z, color.w)
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::locale::Strings;
+
+pub struct ModifiersPlugin;
+
+impl Plugin for ModifiersPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ActiveModifiers::default())
+            .insert_resource(SelectedModifiers::default());
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Modifier {
+    LowGravity,
+    NoManualBoosts,
+    DoubleClouds,
+    ReverseGravityBursts,
+    InvisibleClouds,
+    MirrorControls,
+    OneHitGameOver,
+}
+
+impl Modifier {
+    pub const ALL: [Modifier; 7] = [
+        Modifier::LowGravity,
+        Modifier::NoManualBoosts,
+        Modifier::DoubleClouds,
+        Modifier::ReverseGravityBursts,
+        Modifier::InvisibleClouds,
+        Modifier::MirrorControls,
+        Modifier::OneHitGameOver,
+    ];
+
+    /// Modifiers that the daily challenge picks from.
+    pub const DAILY: [Modifier; 3] = [
+        Modifier::LowGravity,
+        Modifier::NoManualBoosts,
+        Modifier::DoubleClouds,
+    ];
+
+    pub fn name<'a>(&self, strings: &'a Strings) -> &'a str {
+        strings.get(match self {
+            Modifier::LowGravity => "modifier.low_gravity",
+            Modifier::NoManualBoosts => "modifier.no_manual_boosts",
+            Modifier::DoubleClouds => "modifier.double_clouds",
+            Modifier::ReverseGravityBursts => "modifier.reverse_gravity_bursts",
+            Modifier::InvisibleClouds => "modifier.invisible_clouds",
+            Modifier::MirrorControls => "modifier.mirror_controls",
+            Modifier::OneHitGameOver => "modifier.one_hit_game_over",
+        })
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        match self {
+            Modifier::LowGravity => 0.8,
+            Modifier::NoManualBoosts => 1.3,
+            Modifier::DoubleClouds => 1.5,
+            Modifier::ReverseGravityBursts => 1.2,
+            Modifier::InvisibleClouds => 1.4,
+            Modifier::MirrorControls => 1.2,
+            Modifier::OneHitGameOver => 2.0,
+        }
+    }
+}
+
+/// Modifiers in effect for the current run.
+#[derive(Resource, Default, Clone)]
+pub struct ActiveModifiers(pub HashSet<Modifier>);
+
+impl ActiveModifiers {
+    pub fn has(&self, modifier: Modifier) -> bool {
+        self.0.contains(&modifier)
+    }
+
+    pub fn toggle(&mut self, modifier: Modifier) {
+        if !self.0.remove(&modifier) {
+            self.0.insert(modifier);
+        }
+    }
+
+    pub fn describe(&self, strings: &Strings) -> String {
+        Modifier::ALL
+            .iter()
+            .filter(|modifier| self.has(**modifier))
+            .map(|modifier| modifier.name(strings))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|modifier| modifier.score_multiplier())
+            .product()
+    }
+}
+
+/// Modifiers chosen by the player on the launch screen, used by endless runs.
+#[derive(Resource, Default)]
+pub struct SelectedModifiers(pub ActiveModifiers);
@@ -1,48 +1,138 @@
 use bevy::prelude::*;
 
-use crate::{app_state::AppState, coin::Coin, coin_camera::COIN_SCREEN_BOUNDS_Y};
+use crate::{
+    app_state::AppState,
+    coin::{Coin, Launcher},
+    coin_camera::CoinCamera,
+    display::PLAYFIELD_HEIGHT,
+    level::SPAWN_MAX_Y,
+    player::{for_player, PlayerId},
+    pool::{Pooled, Pools},
+};
 
 pub struct PhysicsPlugin;
 
 impl Plugin for PhysicsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.init_resource::<TimeScale>().add_systems(
             Update,
-            (update_y_pos_relative_to_coin, despawn_out_of_bounds_things)
+            (
+                update_y_pos_relative_to_coin,
+                (despawn_out_of_bounds_things, hide_things_out_of_view),
+            )
+                .chain()
                 .run_if(in_state(AppState::Ingame)),
         );
     }
 }
 
+/// Speed of the flight, e.g. slowed down during a rescue. Unlike `Time::set_relative_speed`, it
+/// leaves the tweens, transitions and sounds at full speed.
+#[derive(Resource)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl TimeScale {
+    /// Flight time that passed since the last frame.
+    pub fn delta_seconds(&self, time: &Time) -> f32 {
+        time.delta_seconds() * self.0
+    }
+}
+
+/// Scrolls the entity with the speed of the coin that has the same `PlayerId`.
 #[derive(Component)]
 pub struct RelativeCoinY;
 
 fn update_y_pos_relative_to_coin(
     time: Res<Time>,
-    coin_query: Query<&Coin>,
-    mut query: Query<&mut Transform, With<RelativeCoinY>>,
+    time_scale: Res<TimeScale>,
+    coin_query: Query<(&Coin, &PlayerId)>,
+    mut query: Query<(&mut Transform, &PlayerId), With<RelativeCoinY>>,
 ) {
-    let coin = match coin_query.get_single() {
-        Ok(coin) => coin,
-        Err(_) => return,
-    };
-
-    let adjustments = coin.speed * time.delta_seconds();
+    let delta_seconds = time_scale.delta_seconds(&time);
+    coin_query.for_each(|(coin, coin_player)| {
+        let adjustments = coin.speed * delta_seconds;
 
-    query.for_each_mut(|mut transform| {
-        transform.translation.y -= adjustments;
+        query.for_each_mut(|(mut transform, player)| {
+            if player == coin_player {
+                transform.translation.y -= adjustments;
+            }
+        });
     });
 }
 
-const OUT_OF_BOUNDS_Y: f32 = COIN_SCREEN_BOUNDS_Y * -10.0;
+/// Things scrolled this far below the view are only seen again if the coin falls a long way.
+const OUT_OF_BOUNDS_BELOW_VIEW: f32 = PLAYFIELD_HEIGHT * 3.0;
+/// New things spawn up to `SPAWN_MAX_Y` above the view, so only what the coin fell away from is
+/// further up.
+const OUT_OF_BOUNDS_ABOVE_VIEW: f32 = SPAWN_MAX_Y + PLAYFIELD_HEIGHT;
+/// Height of the band around each view that things are kept in.
+pub const CULLING_BAND_HEIGHT: f32 = OUT_OF_BOUNDS_BELOW_VIEW + OUT_OF_BOUNDS_ABOVE_VIEW;
+
+/// Height of the center of the player's view, things are culled relative to it.
+pub fn view_center_y(
+    camera_query: &Query<(&Transform, &PlayerId), With<CoinCamera>>,
+    player: &PlayerId,
+) -> Option<f32> {
+    for_player(camera_query.iter(), player).map(|transform| transform.translation.y)
+}
 
 fn despawn_out_of_bounds_things(
     mut commands: Commands,
-    query: Query<(&Transform, Entity), With<RelativeCoinY>>,
+    mut pools: ResMut<Pools>,
+    camera_query: Query<(&Transform, &PlayerId), With<CoinCamera>>,
+    query: Query<(&Transform, &PlayerId, Entity, Option<&Pooled>), With<RelativeCoinY>>,
+    launcher_query: Query<(), With<Launcher>>,
 ) {
-    query.for_each(|(transform, entity)| {
-        if transform.translation.y < OUT_OF_BOUNDS_Y {
-            commands.get_entity(entity).unwrap().despawn();
+    query.for_each(|(transform, player, entity, pooled)| {
+        // the launcher is kept for the coins to land in
+        if launcher_query.contains(entity) {
+            return;
+        }
+        let view_y = match view_center_y(&camera_query, player) {
+            Some(view_y) => view_y,
+            None => return,
+        };
+        let y = transform.translation.y - view_y;
+        if (-OUT_OF_BOUNDS_BELOW_VIEW..=OUT_OF_BOUNDS_ABOVE_VIEW).contains(&y) {
+            return;
         }
+
+        match pooled {
+            Some(pooled) => pools.release(&mut commands, entity, pooled.0, *player),
+            None => commands.get_entity(entity).unwrap().despawn(),
+        }
+    });
+}
+
+/// Room around the view for the biggest sprites, so nothing pops in at the edges.
+const VIEW_MARGIN: f32 = 200.0;
+
+/// Whether something at `y` can be seen from the view centered at `view_y`.
+pub fn is_in_view(y: f32, view_y: f32) -> bool {
+    (y - view_y).abs() <= PLAYFIELD_HEIGHT * 0.5 + VIEW_MARGIN
+}
+
+/// Hides what is scrolled out of view, which also pauses its frame animation.
+fn hide_things_out_of_view(
+    camera_query: Query<(&Transform, &PlayerId), With<CoinCamera>>,
+    mut query: Query<(&Transform, &PlayerId, &mut Visibility), With<RelativeCoinY>>,
+) {
+    query.for_each_mut(|(transform, player, mut visibility)| {
+        let view_y = match view_center_y(&camera_query, player) {
+            Some(view_y) => view_y,
+            None => return,
+        };
+
+        visibility.set_if_neq(if is_in_view(transform.translation.y, view_y) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
     });
 }
@@ -0,0 +1,84 @@
+use bevy::{prelude::*, render::view::RenderLayers};
+
+use crate::locale::Strings;
+
+/// Which player a coin, and everything in that coin's view, belongs to.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct PlayerId(pub usize);
+
+impl PlayerId {
+    pub fn all(player_count: usize) -> impl Iterator<Item = PlayerId> {
+        (0..player_count).map(PlayerId)
+    }
+
+    /// Each player's world is drawn on its own layer, so only that player's camera sees it.
+    pub fn render_layers(&self) -> RenderLayers {
+        RenderLayers::layer(self.0 as u8 + 1)
+    }
+
+    pub fn name(&self, strings: &Strings) -> String {
+        strings.format("player.name", &[("number", &(self.0 + 1))])
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+pub struct PlayerControls {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub boost: KeyCode,
+}
+
+impl PlayerControls {
+    pub fn for_player(player: PlayerId, player_count: usize) -> Self {
+        match (player_count, player.0) {
+            (1, _) => Self {
+                left: KeyCode::Left,
+                right: KeyCode::Right,
+                boost: KeyCode::Space,
+            },
+            (_, 0) => Self {
+                left: KeyCode::A,
+                right: KeyCode::D,
+                boost: KeyCode::W,
+            },
+            _ => Self {
+                left: KeyCode::Left,
+                right: KeyCode::Right,
+                boost: KeyCode::Up,
+            },
+        }
+    }
+
+    pub fn describe(&self, strings: &Strings) -> String {
+        strings.format(
+            "player.controls",
+            &[
+                ("left", &key_name(self.left)),
+                ("right", &key_name(self.right)),
+                ("boost", &key_name(self.boost)),
+            ],
+        )
+    }
+}
+
+pub fn key_name(key: KeyCode) -> String {
+    match key {
+        KeyCode::Left => "LEFT".to_string(),
+        KeyCode::Right => "RIGHT".to_string(),
+        KeyCode::Up => "UP".to_string(),
+        KeyCode::Space => "SPACE".to_string(),
+        _ => format!("{:?}", key)
+            .trim_start_matches("Key")
+            .to_uppercase(),
+    }
+}
+
+/// Picks the item belonging to `player`, e.g. out of a `Query<(&Coin, &PlayerId)>`.
+pub fn for_player<'a, T>(
+    mut items: impl Iterator<Item = (T, &'a PlayerId)>,
+    player: &PlayerId,
+) -> Option<T> {
+    items
+        .find(|(_, item_player)| *item_player == player)
+        .map(|(item, _)| item)
+}
@@ -0,0 +1,290 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    anim::{FrameAnimation, Tween},
+    app_state::{AppState, StateOwner},
+    boost_item::BoostItem,
+    cloud::Cloud,
+    coin_camera::CoinCamera,
+    fairy::Fairy,
+    game_assets::{FontAssets, TextureAssets},
+    level,
+    physics::{self, RelativeCoinY},
+    player::PlayerId,
+    scoring::PassedCloud,
+    theme::Theme,
+};
+
+pub struct PoolPlugin;
+
+impl Plugin for PoolPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Pools>()
+            .add_systems(
+                OnEnter(AppState::Ingame),
+                (reset_pools, setup_pool_stats_ui),
+            )
+            .add_systems(
+                Update,
+                (toggle_pool_stats_ui, update_pool_stats_ui)
+                    .chain()
+                    .run_if(in_state(AppState::Ingame)),
+            );
+    }
+}
+
+/// Objects that are hidden and reused instead of despawned, as lots of them come and go.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PoolKind {
+    Cloud,
+    Fairy,
+    BoostItem,
+}
+
+impl PoolKind {
+    const ALL: [PoolKind; 3] = [PoolKind::Cloud, PoolKind::Fairy, PoolKind::BoostItem];
+
+    /// Most objects of the kind one player has in use at once. The level never spawns more than
+    /// fit into the culling band, so past it the oldest one out of view is taken for the new one,
+    /// or nothing is spawned.
+    fn live_cap(&self) -> usize {
+        level::max_live_objects(*self)
+    }
+}
+
+/// Put on every pooled entity, hidden or not.
+#[derive(Component)]
+pub struct Pooled(pub PoolKind);
+
+#[derive(Default, Clone, Copy)]
+struct PoolStats {
+    created: usize,
+    reused: usize,
+    /// Times the oldest live entity was taken because of the `live_cap`.
+    taken: usize,
+    /// Spawns left out at the `live_cap`, as every live entity was in view.
+    skipped: usize,
+    /// Most entities one player had in use at once.
+    peak_live: usize,
+}
+
+#[derive(Resource, Default)]
+pub struct Pools {
+    free: HashMap<PoolKind, Vec<Entity>>,
+    /// Entities in use by each player, oldest first.
+    live: HashMap<(PoolKind, PlayerId), VecDeque<Entity>>,
+    stats: HashMap<PoolKind, PoolStats>,
+}
+
+impl Pools {
+    /// A hidden entity to reuse, or a new one, and whether it is new. `None` when the player is
+    /// at the cap and every live entity is in view, as taking one would make it vanish.
+    fn acquire(
+        &mut self,
+        commands: &mut Commands,
+        kind: PoolKind,
+        player: PlayerId,
+        is_in_view: impl Fn(Entity) -> bool,
+    ) -> Option<(Entity, bool)> {
+        let live = self.live.entry((kind, player)).or_default();
+        let stats = self.stats.entry(kind).or_default();
+        let free = self.free.entry(kind).or_default();
+
+        if live.len() >= kind.live_cap() {
+            // the oldest is usually far below
+            let taken = live
+                .iter()
+                .position(|entity| !is_in_view(*entity))
+                .and_then(|index| live.remove(index));
+            match taken {
+                Some(taken) => {
+                    strip(commands, taken, kind);
+                    free.push(taken);
+                    stats.taken += 1;
+                }
+                None => {
+                    stats.skipped += 1;
+                    return None;
+                }
+            }
+        }
+
+        let acquired = match free.pop() {
+            Some(entity) => {
+                stats.reused += 1;
+                (entity, false)
+            }
+            None => {
+                stats.created += 1;
+                (
+                    commands
+                        .spawn((Pooled(kind), StateOwner(AppState::Ingame)))
+                        .id(),
+                    true,
+                )
+            }
+        };
+
+        live.push_back(acquired.0);
+        stats.peak_live = stats.peak_live.max(live.len());
+        Some(acquired)
+    }
+
+    /// Hides the entity and strips what makes it a cloud, fairy or boost for the gameplay, until
+    /// it is reused. Its children stay, they are the same every time.
+    pub fn release(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        kind: PoolKind,
+        player: PlayerId,
+    ) {
+        let live = self.live.entry((kind, player)).or_default();
+        // e.g. touched in the same frame it went out of bounds
+        let index = match live.iter().position(|live| *live == entity) {
+            Some(index) => index,
+            None => return,
+        };
+        live.remove(index);
+        self.free.entry(kind).or_default().push(entity);
+
+        commands.entity(entity).insert(Visibility::Hidden);
+        strip(commands, entity, kind);
+    }
+}
+
+/// Removes what makes the entity a cloud, fairy or boost for the gameplay.
+fn strip(commands: &mut Commands, entity: Entity, kind: PoolKind) {
+    let mut entity_commands = commands.entity(entity);
+    match kind {
+        PoolKind::Cloud => {
+            entity_commands.remove::<(Cloud, RelativeCoinY, PassedCloud)>();
+        }
+        PoolKind::Fairy => {
+            entity_commands.remove::<(Fairy, RelativeCoinY, FrameAnimation)>();
+        }
+        PoolKind::BoostItem => {
+            entity_commands.remove::<(BoostItem, RelativeCoinY, FrameAnimation, Tween)>();
+        }
+    }
+}
+
+/// Spawns clouds, fairies and boosts from their pools.
+#[derive(SystemParam)]
+pub struct PoolSpawner<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    pub texture_assets: Res<'w, TextureAssets>,
+    pub theme: Res<'w, Theme>,
+    pools: ResMut<'w, Pools>,
+    camera_query: Query<'w, 's, (&'static Transform, &'static PlayerId), With<CoinCamera>>,
+    pooled_query: Query<'w, 's, &'static Transform, With<Pooled>>,
+}
+
+impl PoolSpawner<'_, '_> {
+    /// Entity to insert the object's components into, and whether it is new and still needs its
+    /// children. `None` if the player has too many objects of the kind in view to spawn another.
+    pub fn acquire(&mut self, kind: PoolKind, player: PlayerId) -> Option<(Entity, bool)> {
+        let view_y = physics::view_center_y(&self.camera_query, &player);
+        let pooled_query = &self.pooled_query;
+        self.pools
+            .acquire(&mut self.commands, kind, player, |entity| {
+                // without a view or a position yet, it may just be about to show up
+                match (view_y, pooled_query.get(entity)) {
+                    (Some(view_y), Ok(transform)) => {
+                        physics::is_in_view(transform.translation.y, view_y)
+                    }
+                    _ => true,
+                }
+            })
+    }
+}
+
+/// The pooled entities are despawned with the rest of the flight.
+fn reset_pools(mut pools: ResMut<Pools>) {
+    *pools = Pools::default();
+}
+
+/// Debug view of the pools, toggled with F3.
+#[derive(Component)]
+struct PoolStatsUi;
+
+fn setup_pool_stats_ui(mut commands: Commands, font_assets: Res<FontAssets>, theme: Res<Theme>) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: font_assets.font_fira.clone(),
+                font_size: 16.0,
+                color: theme.colors.text,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            display: Display::None,
+            ..Default::default()
+        })
+        .with_background_color(Color::BLACK.with_a(0.6)),
+        PoolStatsUi,
+        StateOwner(AppState::Ingame),
+    ));
+}
+
+fn toggle_pool_stats_ui(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut query: Query<&mut Style, With<PoolStatsUi>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F3) {
+        return;
+    }
+
+    query.for_each_mut(|mut style| {
+        style.display = match style.display {
+            Display::None => Display::Flex,
+            _ => Display::None,
+        };
+    });
+}
+
+fn update_pool_stats_ui(
+    pools: Res<Pools>,
+    mut query: Query<(&mut Text, &Style), With<PoolStatsUi>>,
+) {
+    query.for_each_mut(|(mut text, style)| {
+        if style.display == Display::None {
+            return;
+        }
+
+        // developer view, not translated
+        text.sections[0].value = PoolKind::ALL
+            .iter()
+            .map(|kind| {
+                let stats = pools.stats.get(kind).copied().unwrap_or_default();
+                let free = pools.free.get(kind).map_or(0, Vec::len);
+                let live = pools
+                    .live
+                    .iter()
+                    .filter(|((live_kind, _), _)| live_kind == kind)
+                    .map(|(_, live)| live.len())
+                    .sum::<usize>();
+                format!(
+                    "{:?}: {} live ({} peak, {} cap per player), {} free, {} created, {} reused, \
+                     {} taken, {} skipped",
+                    kind,
+                    live,
+                    stats.peak_live,
+                    kind.live_cap(),
+                    free,
+                    stats.created,
+                    stats.reused,
+                    stats.taken,
+                    stats.skipped,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    });
+}
@@ -0,0 +1,221 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    app_state::{AppState, FlightPhase, StateOwner},
+    boost_item::BoostItem,
+    coin::{Coin, OutOfRace, COIN_ADJUSTMENT_X_SPEED, COIN_FULL_SIZE},
+    display::PlayfieldUi,
+    fairy::Fairy,
+    game_assets::FontAssets,
+    locale::Strings,
+    physics::TimeScale,
+    player::{key_name, PlayerId},
+    theme::Theme,
+    transition::not_leaving_state,
+    upgrades::Upgrades,
+};
+
+pub struct RescuePlugin;
+
+impl Plugin for RescuePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rescue>()
+            .add_systems(OnEnter(AppState::Ingame), reset_rescue)
+            .add_systems(
+                OnEnter(FlightPhase::Rescue),
+                (start_rescue, setup_rescue_ui),
+            )
+            .add_systems(
+                OnExit(FlightPhase::Rescue),
+                (restore_time_speed, remove_rescue_ui),
+            )
+            .add_systems(
+                Update,
+                (revive_coins, end_rescue)
+                    .chain()
+                    .run_if(in_state(AppState::Ingame))
+                    .run_if(in_state(FlightPhase::Rescue))
+                    .run_if(not_leaving_state),
+            );
+    }
+}
+
+/// Speed of the flight while a coin can still be rescued.
+const RESCUE_TIME_SPEED: f32 = 0.25;
+/// Flight time the rescue lasts, it takes longer in real time.
+const RESCUE_SECONDS: f32 = 1.0;
+/// Room to spare when telling whether a pickup can still be reached.
+const RESCUE_REACH_MARGIN: f32 = COIN_FULL_SIZE.x;
+
+const REVIVE_COST: u32 = 25;
+const REVIVE_SPEED: f32 = 400.0;
+const REVIVE_KEY: KeyCode = KeyCode::Return;
+
+#[derive(Resource, Default)]
+struct Rescue {
+    /// Flight time left to get the coins back in the air.
+    time_left: f32,
+    /// Revives are once per flight.
+    revive_used: bool,
+}
+
+/// Whether a coin that is out can still be saved: a fairy or boost is close enough to steer to,
+/// or there is fae dust for a revive.
+#[derive(SystemParam)]
+pub struct RescueChance<'w, 's> {
+    rescue: Res<'w, Rescue>,
+    upgrades: Res<'w, Upgrades>,
+    coin_query:
+        Query<'w, 's, (&'static Coin, &'static Transform, &'static PlayerId), Without<OutOfRace>>,
+    fairy_query: Query<'w, 's, (&'static Transform, &'static PlayerId), With<Fairy>>,
+    boost_item_query: Query<'w, 's, (&'static Transform, &'static PlayerId), With<BoostItem>>,
+}
+
+impl RescueChance<'_, '_> {
+    pub fn exists(&self) -> bool {
+        self.can_revive() || self.pickup_reachable()
+    }
+
+    fn can_revive(&self) -> bool {
+        !self.rescue.revive_used && self.upgrades.fae_dust >= REVIVE_COST
+    }
+
+    /// Every coin that is out has a pickup coming its way that it can steer into in time.
+    fn pickup_reachable(&self) -> bool {
+        self.coin_query
+            .iter()
+            .filter(|(coin, _, _)| coin.is_out())
+            .all(|(coin, coin_transform, coin_player)| {
+                self.fairy_query
+                    .iter()
+                    .chain(self.boost_item_query.iter())
+                    .any(|(transform, player)| {
+                        player == coin_player && is_reachable(coin, coin_transform, transform)
+                    })
+            })
+    }
+}
+
+/// The coin is falling, so pickups below it come closer.
+fn is_reachable(coin: &Coin, coin_transform: &Transform, transform: &Transform) -> bool {
+    let below = coin_transform.translation.y - transform.translation.y;
+    let seconds = below / -coin.speed;
+    if !(0.0..=RESCUE_SECONDS).contains(&seconds) {
+        return false;
+    }
+
+    let aside = (coin_transform.translation.x - transform.translation.x).abs();
+    aside <= COIN_ADJUSTMENT_X_SPEED * seconds + RESCUE_REACH_MARGIN
+}
+
+#[derive(Component)]
+struct RescueUi;
+
+fn reset_rescue(mut rescue: ResMut<Rescue>) {
+    *rescue = Rescue::default();
+}
+
+fn start_rescue(mut rescue: ResMut<Rescue>, mut time_scale: ResMut<TimeScale>) {
+    rescue.time_left = RESCUE_SECONDS;
+    time_scale.0 = RESCUE_TIME_SPEED;
+}
+
+fn restore_time_speed(mut time_scale: ResMut<TimeScale>) {
+    time_scale.0 = 1.0;
+}
+
+fn setup_rescue_ui(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    rescue_chance: RescueChance,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(12.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            PlayfieldUi,
+            RescueUi,
+            StateOwner(AppState::Ingame),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                strings.get("rescue.title"),
+                theme.text_style(&font_assets.font_fira, 48.0, theme.colors.danger),
+            ));
+
+            if rescue_chance.pickup_reachable() {
+                parent.spawn(TextBundle::from_section(
+                    strings.get("rescue.steer"),
+                    theme.text_style(&font_assets.font_fira, 28.0, theme.colors.pickup),
+                ));
+            }
+
+            if rescue_chance.can_revive() {
+                parent.spawn(TextBundle::from_section(
+                    strings.format(
+                        "rescue.revive",
+                        &[("key", &key_name(REVIVE_KEY)), ("cost", &REVIVE_COST)],
+                    ),
+                    theme.text_style(&font_assets.font_fira, 28.0, theme.colors.accent),
+                ));
+            }
+        });
+}
+
+fn remove_rescue_ui(mut commands: Commands, query: Query<Entity, With<RescueUi>>) {
+    query.for_each(|entity| {
+        commands.entity(entity).despawn_recursive();
+    });
+}
+
+fn revive_coins(
+    keyboard: Res<Input<KeyCode>>,
+    mut rescue: ResMut<Rescue>,
+    mut upgrades: ResMut<Upgrades>,
+    mut query: Query<&mut Coin, Without<OutOfRace>>,
+) {
+    if !keyboard.just_pressed(REVIVE_KEY) || rescue.revive_used || !upgrades.spend(REVIVE_COST) {
+        return;
+    }
+
+    rescue.revive_used = true;
+    query.for_each_mut(|mut coin| {
+        if coin.is_out() {
+            coin.speed = REVIVE_SPEED;
+        }
+    });
+}
+
+/// Back to flying once every coin is saved, or when the time is up and the coins that are still
+/// falling are out.
+fn end_rescue(
+    mut commands: Commands,
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut rescue: ResMut<Rescue>,
+    mut next_phase: ResMut<NextState<FlightPhase>>,
+    query: Query<(&Coin, Entity), Without<OutOfRace>>,
+) {
+    rescue.time_left -= time_scale.delta_seconds(&time);
+
+    if !query.iter().any(|(coin, _)| coin.is_out()) {
+        next_phase.set(FlightPhase::Flying);
+    } else if rescue.time_left <= 0.0 {
+        query.for_each(|(coin, entity)| {
+            if coin.is_out() {
+                commands.entity(entity).insert(OutOfRace);
+            }
+        });
+        next_phase.set(FlightPhase::Flying);
+    }
+}
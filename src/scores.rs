@@ -1,34 +1,163 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
 
 pub struct ScoresPlugin;
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct Scores {
     pub end_score: i32,
     pub best_score: i32,
     pub new_record: bool,
+    /// Highest altitude (in metres) of the last run, tracked apart from the score.
+    pub end_altitude: i32,
+    pub best_altitude: i32,
+    pub new_altitude_record: bool,
+    /// Score multiplier from the modifiers of the last run.
+    pub end_multiplier: f32,
+    /// Scores of each player in the last race, in player order.
+    pub end_race_scores: Vec<i32>,
+    /// Best score of the daily challenge of `daily_best_day`, every day has its own challenge.
+    daily_best_score: i32,
+    daily_best_day: Option<u64>,
+    last_daily_day: Option<u64>,
+    /// Best time attack run for each goal altitude (in metres).
+    pub best_times: HashMap<u32, TimeAttackRecord>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TimeAttackRecord {
+    pub time: f32,
+    pub splits: Vec<f32>,
 }
 
-impl Default for Scores {
-    fn default() -> Self {
+const SCORES_SAVE_KEY: &str = "scores";
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SavedScores {
+    best_score: i32,
+    best_altitude: i32,
+    daily_best_score: i32,
+    daily_best_day: Option<u64>,
+    last_daily_day: Option<u64>,
+    best_times: HashMap<u32, TimeAttackRecord>,
+}
+
+impl Scores {
+    fn load() -> Self {
+        let saved: SavedScores = storage::load(SCORES_SAVE_KEY).unwrap_or_default();
+
         Self {
-            end_score: 0,
-            best_score: 0,
-            new_record: false,
+            best_score: saved.best_score,
+            best_altitude: saved.best_altitude,
+            daily_best_score: saved.daily_best_score,
+            daily_best_day: saved.daily_best_day,
+            last_daily_day: saved.last_daily_day,
+            best_times: saved.best_times,
+            ..Default::default()
         }
     }
-}
 
-impl Scores {
+    fn save(&self) {
+        storage::save(
+            SCORES_SAVE_KEY,
+            &SavedScores {
+                best_score: self.best_score,
+                best_altitude: self.best_altitude,
+                daily_best_score: self.daily_best_score,
+                daily_best_day: self.daily_best_day,
+                last_daily_day: self.last_daily_day,
+                best_times: self.best_times.clone(),
+            },
+        );
+    }
+
     pub fn register_score(&mut self, new_score: i32) {
         self.new_record = new_score > self.best_score;
         self.end_score = new_score;
         self.best_score = self.best_score.max(self.end_score);
+
+        if self.new_record {
+            self.save();
+        }
+    }
+
+    /// Records the highest altitude of a run; only `ranked` runs can set a new best altitude.
+    pub fn register_altitude(&mut self, altitude: i32, ranked: bool) {
+        self.new_altitude_record = ranked && altitude > self.best_altitude;
+        self.end_altitude = altitude;
+
+        if self.new_altitude_record {
+            self.best_altitude = altitude;
+            self.save();
+        }
+    }
+
+    /// Records the score of a run that does not compete for a best score.
+    pub fn register_unranked_score(&mut self, new_score: i32) {
+        self.new_record = false;
+        self.end_score = new_score;
+    }
+
+    pub fn register_race_scores(&mut self, race_scores: Vec<i32>) {
+        self.register_unranked_score(race_scores.iter().copied().max().unwrap_or_default());
+        self.end_race_scores = race_scores;
+    }
+
+    /// Returns whether the run is the new best time for the goal.
+    pub fn register_time_attack(&mut self, goal: u32, record: TimeAttackRecord) -> bool {
+        let is_best = match self.best_times.get(&goal) {
+            Some(best) => record.time < best.time,
+            None => true,
+        };
+
+        if is_best {
+            self.best_times.insert(goal, record);
+            self.save();
+        }
+
+        is_best
+    }
+
+    pub fn has_attempted_daily(&self, day: u64) -> bool {
+        self.last_daily_day == Some(day)
+    }
+
+    pub fn register_daily_attempt(&mut self, day: u64) {
+        self.last_daily_day = Some(day);
+        if self.daily_best_day != Some(day) {
+            self.daily_best_score = 0;
+            self.daily_best_day = Some(day);
+        }
+        self.save();
+    }
+
+    /// Best score of the daily challenge of `day`, 0 if it wasn't played.
+    pub fn daily_best(&self, day: u64) -> i32 {
+        match self.daily_best_day {
+            Some(best_day) if best_day == day => self.daily_best_score,
+            _ => 0,
+        }
+    }
+
+    pub fn register_daily_score(&mut self, new_score: i32, day: u64, scored: bool) {
+        self.new_record = scored && new_score > self.daily_best(day);
+        self.end_score = new_score;
+
+        if self.new_record {
+            self.daily_best_score = new_score;
+            self.daily_best_day = Some(day);
+            self.save();
+        }
     }
 }
 
 impl Plugin for ScoresPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Scores::default());
+        app.insert_resource(Scores::load());
     }
 }
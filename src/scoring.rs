@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    cloud::{Cloud, CloudHitEvent, NearMissEvent},
+    coin::{Coin, ManualBoostUsedEvent},
+    fairy::FairyTouchedEvent,
+    player::{for_player, PlayerId},
+};
+
+pub struct ScoringPlugin;
+
+impl Plugin for ScoringPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                score_fairy_chains,
+                score_manual_boosts,
+                score_near_misses,
+                score_passed_clouds,
+            )
+                .run_if(in_state(AppState::Ingame)),
+        );
+    }
+}
+
+/// Style points a coin earned during the run, on top of the altitude reached.
+#[derive(Component, Default)]
+pub struct RunScore {
+    pub points: f32,
+    /// Fairies touched in a row without hitting a cloud.
+    pub chain: u32,
+    last_passed_cloud: Option<PassedCloudInfo>,
+}
+
+struct PassedCloudInfo {
+    altitude: f32,
+    rect: Rect,
+}
+
+const CHAIN_MULTIPLIER_STEP: f32 = 0.25;
+const MAX_MULTIPLIER: f32 = 4.0;
+
+impl RunScore {
+    pub fn multiplier(&self) -> f32 {
+        (1.0 + self.chain as f32 * CHAIN_MULTIPLIER_STEP).min(MAX_MULTIPLIER)
+    }
+
+    fn add(&mut self, points: f32) {
+        self.points += points * self.multiplier();
+    }
+
+    /// Score of the run before modifiers: metres climbed plus style points.
+    pub fn total(&self, highest_altitude_recorded: f32) -> f32 {
+        (highest_altitude_recorded.ceil() / 10.0).floor() + self.points.floor()
+    }
+}
+
+/// Marks clouds that already went past the coin, so each one is only scored once.
+#[derive(Component)]
+pub struct PassedCloud;
+
+const FAIRY_CHAIN_POINTS: f32 = 10.0;
+
+fn score_fairy_chains(
+    mut cloud_hit_events: EventReader<CloudHitEvent>,
+    mut fairy_touched_events: EventReader<FairyTouchedEvent>,
+    mut query: Query<(&mut RunScore, &PlayerId)>,
+) {
+    cloud_hit_events.iter().for_each(|event| {
+        query.for_each_mut(|(mut run_score, player)| {
+            if *player == event.player {
+                run_score.chain = 0;
+            }
+        });
+    });
+
+    fairy_touched_events.iter().for_each(|event| {
+        query.for_each_mut(|(mut run_score, player)| {
+            if *player == event.player {
+                run_score.chain += 1;
+                let chain = run_score.chain as f32;
+                run_score.add(FAIRY_CHAIN_POINTS * chain);
+            }
+        });
+    });
+}
+
+const BOOST_EFFICIENCY_POINTS: f32 = 20.0;
+/// Boosting at or above this speed is considered wasteful and earns nothing.
+const BOOST_WASTEFUL_SPEED: f32 = 600.0;
+
+fn score_manual_boosts(
+    mut boost_used_events: EventReader<ManualBoostUsedEvent>,
+    mut query: Query<(&mut RunScore, &PlayerId)>,
+) {
+    boost_used_events.iter().for_each(|event| {
+        let efficiency = 1.0 - (event.speed_before / BOOST_WASTEFUL_SPEED).clamp(0.0, 1.0);
+
+        query.for_each_mut(|(mut run_score, player)| {
+            if *player == event.player {
+                run_score.add(BOOST_EFFICIENCY_POINTS * efficiency);
+            }
+        });
+    });
+}
+
+const NEAR_MISS_POINTS: f32 = 15.0;
+
+fn score_near_misses(
+    mut near_miss_events: EventReader<NearMissEvent>,
+    mut query: Query<(&mut RunScore, &PlayerId)>,
+) {
+    near_miss_events.iter().for_each(|event| {
+        query.for_each_mut(|(mut run_score, player)| {
+            if *player == event.player {
+                run_score.add(NEAR_MISS_POINTS);
+            }
+        });
+    });
+}
+
+/// Clouds closer than this vertically are considered to be on the same row.
+const GAP_ROW_TOLERANCE: f32 = 40.0;
+const NARROW_GAP_WIDTH: f32 = 120.0;
+const NARROW_GAP_POINTS: f32 = 25.0;
+
+fn score_passed_clouds(
+    mut commands: Commands,
+    mut coin_query: Query<(&Coin, &Transform, &mut RunScore, &PlayerId)>,
+    cloud_query: Query<(&Cloud, &Transform, &PlayerId, Entity), Without<PassedCloud>>,
+) {
+    cloud_query.for_each(|(cloud, cloud_transform, player, cloud_entity)| {
+        let (coin, coin_transform, mut run_score) = match for_player(
+            coin_query
+                .iter_mut()
+                .map(|(coin, transform, run_score, player)| ((coin, transform, run_score), player)),
+            player,
+        ) {
+            Some(coin) => coin,
+            None => return,
+        };
+        let coin_rect = Coin::get_bounds(coin_transform);
+        let cloud_rect = Cloud::get_bounds(cloud_transform);
+
+        if cloud_rect.max.y >= coin_rect.min.y {
+            return;
+        }
+
+        commands.entity(cloud_entity).insert(PassedCloud);
+
+        if !cloud.is_active() {
+            return;
+        }
+
+        let passed = PassedCloudInfo {
+            altitude: coin.altitude + cloud_transform.translation.y - coin_transform.translation.y,
+            rect: cloud_rect,
+        };
+
+        if let Some(previous) = &run_score.last_passed_cloud {
+            let (left, right) = if previous.rect.max.x < passed.rect.min.x {
+                (&previous.rect, &passed.rect)
+            } else {
+                (&passed.rect, &previous.rect)
+            };
+            let same_row = (previous.altitude - passed.altitude).abs() < GAP_ROW_TOLERANCE;
+            let threaded = left.max.x <= coin_rect.min.x && right.min.x >= coin_rect.max.x;
+
+            if same_row && threaded && right.min.x - left.max.x < NARROW_GAP_WIDTH {
+                run_score.add(NARROW_GAP_POINTS);
+            }
+        }
+
+        run_score.last_passed_cloud = Some(passed);
+    });
+}
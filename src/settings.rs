@@ -0,0 +1,165 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{display::DisplayOptions, sfx::SoundOptions, storage, theme::AccessibilityOptions};
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Settings::load())
+            .add_event::<SettingChangedEvent>()
+            // early, so the rest of the frame already follows the new settings
+            .add_systems(
+                First,
+                announce_setting_changes.run_if(resource_changed::<Settings>()),
+            );
+    }
+}
+
+/// All the choices of the player that outlive a run, saved whenever they change.
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// ISO 639-1 code of the language of the texts.
+    pub language: String,
+    pub accessibility: AccessibilityOptions,
+    pub display: DisplayOptions,
+    pub sound: SoundOptions,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: "en".to_string(),
+            accessibility: Default::default(),
+            display: Default::default(),
+            sound: Default::default(),
+        }
+    }
+}
+
+const SETTINGS_SAVE_KEY: &str = "settings";
+
+/// Bumped when a setting changes meaning, so older saves can be converted in `Settings::load`.
+/// New settings don't need it, they get their default value.
+const SETTINGS_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct SettingsSave {
+    version: u32,
+    settings: Settings,
+}
+
+/// Keys the settings were saved under before they were gathered here.
+const LEGACY_LANGUAGE_SAVE_KEY: &str = "language";
+const LEGACY_ACCESSIBILITY_SAVE_KEY: &str = "accessibility";
+const LEGACY_DISPLAY_SAVE_KEY: &str = "display";
+
+impl Settings {
+    fn load() -> Self {
+        match storage::load::<SettingsSave>(SETTINGS_SAVE_KEY) {
+            Some(save) => {
+                if save.version > SETTINGS_VERSION {
+                    warn!(
+                        "Settings were saved by a newer version ({}), unknown ones are reset",
+                        save.version
+                    );
+                }
+                save.settings
+            }
+            None => Self::migrate_legacy_saves(),
+        }
+    }
+
+    /// Version 0: every group of options had its own save.
+    fn migrate_legacy_saves() -> Self {
+        let defaults = Self::default();
+
+        Self {
+            language: storage::load(LEGACY_LANGUAGE_SAVE_KEY).unwrap_or(defaults.language),
+            accessibility: storage::load(LEGACY_ACCESSIBILITY_SAVE_KEY)
+                .unwrap_or(defaults.accessibility),
+            display: storage::load(LEGACY_DISPLAY_SAVE_KEY).unwrap_or(defaults.display),
+            sound: defaults.sound,
+        }
+    }
+
+    fn save(&self) {
+        storage::save(
+            SETTINGS_SAVE_KEY,
+            &SettingsSave {
+                version: SETTINGS_VERSION,
+                settings: self.clone(),
+            },
+        );
+    }
+}
+
+/// One value of the `Settings`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Setting {
+    Language,
+    TextSize,
+    Palette,
+    ReducedMotion,
+    UiScale,
+    Fullscreen,
+    SoundVolume,
+}
+
+impl Setting {
+    /// In the order they are shown in the settings screen.
+    pub const ALL: [Setting; 7] = [
+        Setting::Language,
+        Setting::TextSize,
+        Setting::Palette,
+        Setting::ReducedMotion,
+        Setting::UiScale,
+        Setting::Fullscreen,
+        Setting::SoundVolume,
+    ];
+
+    fn differs(&self, a: &Settings, b: &Settings) -> bool {
+        match self {
+            Setting::Language => a.language != b.language,
+            Setting::TextSize => a.accessibility.text_size != b.accessibility.text_size,
+            Setting::Palette => a.accessibility.palette != b.accessibility.palette,
+            Setting::ReducedMotion => {
+                a.accessibility.reduced_motion != b.accessibility.reduced_motion
+            }
+            Setting::UiScale => a.display.ui_scale != b.display.ui_scale,
+            Setting::Fullscreen => a.display.fullscreen != b.display.fullscreen,
+            Setting::SoundVolume => a.sound.volume != b.sound.volume,
+        }
+    }
+
+    pub fn is_accessibility(&self) -> bool {
+        matches!(
+            self,
+            Setting::TextSize | Setting::Palette | Setting::ReducedMotion
+        )
+    }
+}
+
+/// Sent for every setting when the game starts, then whenever one changes.
+#[derive(Event)]
+pub struct SettingChangedEvent(pub Setting);
+
+fn announce_setting_changes(
+    settings: Res<Settings>,
+    mut previous: Local<Option<Settings>>,
+    mut setting_changed_events: EventWriter<SettingChangedEvent>,
+) {
+    Setting::ALL
+        .iter()
+        .filter(|setting| match &*previous {
+            Some(previous) => setting.differs(previous, &settings),
+            None => true,
+        })
+        .for_each(|setting| setting_changed_events.send(SettingChangedEvent(*setting)));
+
+    // also moves legacy saves to the new format on the first run
+    settings.save();
+    *previous = Some(settings.clone());
+}
@@ -0,0 +1,235 @@
+use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+
+use crate::{
+    app_state::{AppState, StateOwner},
+    display::fit_projection,
+    game_assets::FontAssets,
+    locale::Strings,
+    settings::{Setting, SettingChangedEvent, Settings},
+    theme::Theme,
+    transition::{not_leaving_state, ScreenTransition},
+};
+
+pub struct SettingsUiPlugin;
+
+impl Plugin for SettingsUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedSetting>()
+            .add_systems(
+                OnEnter(AppState::Settings),
+                (reset_selected_setting, setup_settings_ui).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    select_setting,
+                    change_setting,
+                    return_to_main_menu,
+                    update_setting_rows.run_if(resource_changed::<SelectedSetting>()),
+                )
+                    .run_if(in_state(AppState::Settings))
+                    .run_if(not_leaving_state),
+            )
+            .add_systems(
+                Update,
+                // respawned, so the new language, sizes and colours show right away
+                (clear_settings_ui, setup_settings_ui)
+                    .chain()
+                    .run_if(in_state(AppState::Settings))
+                    .run_if(on_event::<SettingChangedEvent>()),
+            );
+    }
+}
+
+/// Index in `Setting::ALL` of the row being edited.
+#[derive(Resource, Default)]
+struct SelectedSetting(usize);
+
+#[derive(Component)]
+struct SettingRow(usize);
+
+fn reset_selected_setting(mut selected: ResMut<SelectedSetting>) {
+    selected.0 = 0;
+}
+
+fn label_key(setting: Setting) -> &'static str {
+    match setting {
+        Setting::Language => "settings.language",
+        Setting::TextSize => "settings.text_size",
+        Setting::Palette => "settings.palette",
+        Setting::ReducedMotion => "settings.reduced_motion",
+        Setting::UiScale => "settings.ui_scale",
+        Setting::Fullscreen => "settings.fullscreen",
+        Setting::SoundVolume => "settings.sound_volume",
+    }
+}
+
+fn on_off(strings: &Strings, on: bool) -> String {
+    strings
+        .get(if on { "option.on" } else { "option.off" })
+        .to_string()
+}
+
+fn value_text(setting: Setting, settings: &Settings, strings: &Strings) -> String {
+    match setting {
+        Setting::Language => strings.language().to_string(),
+        Setting::TextSize => settings.accessibility.text_size.name(strings).to_string(),
+        Setting::Palette => settings.accessibility.palette.name(strings).to_string(),
+        Setting::ReducedMotion => on_off(strings, settings.accessibility.reduced_motion),
+        Setting::UiScale => percent(strings, settings.display.ui_scale),
+        Setting::Fullscreen => on_off(strings, settings.display.fullscreen),
+        Setting::SoundVolume => percent(strings, settings.sound.volume),
+    }
+}
+
+fn percent(strings: &Strings, value: f32) -> String {
+    strings.format(
+        "settings.percent",
+        &[("percent", &format!("{:.0}", value * 100.0))],
+    )
+}
+
+fn row_color(theme: &Theme, is_selected: bool) -> Color {
+    if is_selected {
+        theme.colors.accent
+    } else {
+        theme.colors.text
+    }
+}
+
+fn setup_settings_ui(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    settings: Res<Settings>,
+    selected: Res<SelectedSetting>,
+) {
+    commands.spawn((
+        Camera2dBundle {
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::Custom(theme.colors.background),
+            },
+            projection: fit_projection(),
+            ..Default::default()
+        },
+        StateOwner(AppState::Settings),
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(16.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            StateOwner(AppState::Settings),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                strings.get("settings.title"),
+                TextStyle {
+                    font_size: theme.font_size(48.0),
+                    color: theme.colors.title,
+                    ..Default::default()
+                },
+            ));
+
+            Setting::ALL
+                .iter()
+                .enumerate()
+                .for_each(|(index, setting)| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            strings.format(
+                                "settings.row",
+                                &[
+                                    ("setting", &strings.get(label_key(*setting))),
+                                    ("value", &value_text(*setting, &settings, &strings)),
+                                ],
+                            ),
+                            theme.text_style(
+                                &font_assets.font_fira,
+                                28.0,
+                                row_color(&theme, index == selected.0),
+                            ),
+                        ),
+                        SettingRow(index),
+                    ));
+                });
+
+            parent.spawn(TextBundle::from_section(
+                strings.get("settings.controls"),
+                theme.text_style(&font_assets.font_fira, 24.0, theme.colors.positive),
+            ));
+        });
+}
+
+fn clear_settings_ui(mut commands: Commands, query: Query<(Entity, &StateOwner)>) {
+    query.for_each(|(entity, owner)| {
+        if owner.0 == AppState::Settings {
+            commands.entity(entity).despawn_recursive();
+        }
+    });
+}
+
+fn select_setting(keyboard_input: Res<Input<KeyCode>>, mut selected: ResMut<SelectedSetting>) {
+    let count = Setting::ALL.len();
+
+    if keyboard_input.just_pressed(KeyCode::Down) {
+        selected.0 = (selected.0 + 1) % count;
+    } else if keyboard_input.just_pressed(KeyCode::Up) {
+        selected.0 = (selected.0 + count - 1) % count;
+    }
+}
+
+fn change_setting(
+    keyboard_input: Res<Input<KeyCode>>,
+    selected: Res<SelectedSetting>,
+    strings: Res<Strings>,
+    mut settings: ResMut<Settings>,
+) {
+    if !keyboard_input.any_just_pressed([KeyCode::Return, KeyCode::Right]) {
+        return;
+    }
+
+    match Setting::ALL[selected.0] {
+        Setting::Language => settings.language = strings.next_language(&settings.language),
+        Setting::TextSize => {
+            settings.accessibility.text_size = settings.accessibility.text_size.next()
+        }
+        Setting::Palette => settings.accessibility.palette = settings.accessibility.palette.next(),
+        Setting::ReducedMotion => {
+            settings.accessibility.reduced_motion = !settings.accessibility.reduced_motion
+        }
+        Setting::UiScale => settings.display.ui_scale = settings.display.next_ui_scale(),
+        Setting::Fullscreen => settings.display.fullscreen = !settings.display.fullscreen,
+        Setting::SoundVolume => settings.sound.volume = settings.sound.next_volume(),
+    }
+}
+
+fn update_setting_rows(
+    theme: Res<Theme>,
+    selected: Res<SelectedSetting>,
+    mut query: Query<(&mut Text, &SettingRow)>,
+) {
+    query.for_each_mut(|(mut text, row)| {
+        text.sections[0].style.color = row_color(&theme, row.0 == selected.0);
+    });
+}
+
+fn return_to_main_menu(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        transition.go_to(AppState::MainMenu);
+    }
+}
@@ -0,0 +1,181 @@
+use std::{f32::consts::TAU, sync::Arc, time::Duration};
+
+use bevy::{
+    audio::{AddAudioSource, Source},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::settings::{Setting, SettingChangedEvent, Settings};
+
+pub struct SfxPlugin;
+
+impl Plugin for SfxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Synth>()
+            .add_audio_source::<Synth>()
+            .add_systems(Startup, setup_sfx)
+            .add_systems(PreUpdate, apply_sound_options);
+    }
+}
+
+/// Steps of the volume option.
+const VOLUMES: [f32; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+/// Sound choices of the player.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct SoundOptions {
+    pub volume: f32,
+}
+
+impl Default for SoundOptions {
+    fn default() -> Self {
+        Self { volume: 1.0 }
+    }
+}
+
+impl SoundOptions {
+    pub fn next_volume(&self) -> f32 {
+        let index = VOLUMES
+            .iter()
+            .position(|volume| *volume == self.volume)
+            .map_or(0, |index| index + 1);
+
+        VOLUMES[index % VOLUMES.len()]
+    }
+}
+
+const SAMPLE_RATE: u32 = 44100;
+
+/// A short sound made of decaying tones, generated instead of loaded.
+#[derive(TypeUuid, TypePath)]
+#[uuid = "5d1f7c3e-9a2b-4e6f-8c0d-3b7a1e9f2c64"]
+pub struct Synth {
+    samples: Arc<[f32]>,
+}
+
+impl Synth {
+    /// Two low thumps, "lub-dub".
+    fn heartbeat() -> Self {
+        let mut samples = vec![0.0; (SAMPLE_RATE as f32 * 0.45) as usize];
+        add_tone(&mut samples, 0.0, 55.0, 0.8, 18.0);
+        add_tone(&mut samples, 0.16, 45.0, 0.6, 18.0);
+
+        Self {
+            samples: samples.into(),
+        }
+    }
+
+    /// A coin dropping on metal, with a smaller bounce after it.
+    fn clink() -> Self {
+        let mut samples = vec![0.0; (SAMPLE_RATE as f32 * 0.5) as usize];
+        [(0.0, 0.5), (0.09, 0.25)]
+            .iter()
+            .for_each(|(start, amplitude)| {
+                // inharmonic partials make it sound metallic
+                add_tone(&mut samples, *start, 2350.0, *amplitude, 14.0);
+                add_tone(&mut samples, *start, 3730.0, amplitude * 0.6, 20.0);
+                add_tone(&mut samples, *start, 5210.0, amplitude * 0.3, 30.0);
+            });
+
+        Self {
+            samples: samples.into(),
+        }
+    }
+}
+
+/// A sine tone starting at `start` seconds that dies out, faster for a higher `decay`.
+fn add_tone(samples: &mut [f32], start: f32, frequency: f32, amplitude: f32, decay: f32) {
+    let first = (start * SAMPLE_RATE as f32) as usize;
+
+    samples[first..]
+        .iter_mut()
+        .enumerate()
+        .for_each(|(index, sample)| {
+            let t = index as f32 / SAMPLE_RATE as f32;
+            *sample += amplitude * (-t * decay).exp() * (TAU * frequency * t).sin();
+        });
+}
+
+pub struct SynthDecoder {
+    samples: Arc<[f32]>,
+    index: usize,
+}
+
+impl Iterator for SynthDecoder {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.samples.get(self.index).copied();
+        self.index += 1;
+        sample
+    }
+}
+
+impl Source for SynthDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        Some(self.samples.len().saturating_sub(self.index))
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f32(
+            self.samples.len() as f32 / SAMPLE_RATE as f32,
+        ))
+    }
+}
+
+impl Decodable for Synth {
+    type DecoderItem = f32;
+    type Decoder = SynthDecoder;
+
+    fn decoder(&self) -> SynthDecoder {
+        SynthDecoder {
+            samples: self.samples.clone(),
+            index: 0,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Sfx {
+    pub heartbeat: Handle<Synth>,
+    pub clink: Handle<Synth>,
+}
+
+fn setup_sfx(mut commands: Commands, mut synths: ResMut<Assets<Synth>>) {
+    commands.insert_resource(Sfx {
+        heartbeat: synths.add(Synth::heartbeat()),
+        clink: synths.add(Synth::clink()),
+    });
+}
+
+/// Plays `sound` once.
+pub fn play(commands: &mut Commands, sound: &Handle<Synth>) {
+    commands.spawn(AudioSourceBundle {
+        source: sound.clone(),
+        settings: PlaybackSettings::DESPAWN,
+    });
+}
+
+fn apply_sound_options(
+    settings: Res<Settings>,
+    mut setting_changed_events: EventReader<SettingChangedEvent>,
+    mut global_volume: ResMut<GlobalVolume>,
+) {
+    if setting_changed_events
+        .iter()
+        .any(|event| event.0 == Setting::SoundVolume)
+    {
+        *global_volume = GlobalVolume::new(settings.sound.volume);
+    }
+}
@@ -0,0 +1,202 @@
+use bevy::{core_pipeline::clear_color::ClearColorConfig, prelude::*};
+
+use crate::{
+    app_state::{AppState, StateOwner},
+    display::fit_projection,
+    game_assets::FontAssets,
+    locale::Strings,
+    player::key_name,
+    theme::Theme,
+    transition::ScreenTransition,
+    upgrades::{Upgrade, Upgrades},
+};
+
+pub struct ShopUiPlugin;
+
+impl Plugin for ShopUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Shop), setup_shop_ui)
+            .add_systems(
+                Update,
+                (buy_upgrades, update_shop_ui, leave_shop).run_if(in_state(AppState::Shop)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct FaeDustUi;
+
+#[derive(Component)]
+struct UpgradeUi(Upgrade);
+
+const UPGRADE_KEYS: [KeyCode; 4] = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+
+fn setup_shop_ui(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    upgrades: Res<Upgrades>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+) {
+    commands.spawn((
+        Camera2dBundle {
+            camera_2d: Camera2d {
+                clear_color: ClearColorConfig::Custom(theme.colors.background),
+            },
+            projection: fit_projection(),
+            ..Default::default()
+        },
+        StateOwner(AppState::Shop),
+    ));
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(24.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            StateOwner(AppState::Shop),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                strings.get("shop.title"),
+                TextStyle {
+                    font_size: theme.font_size(48.0),
+                    color: theme.colors.title,
+                    ..Default::default()
+                },
+            ));
+
+            parent.spawn((
+                TextBundle::from_sections([
+                    TextSection::new(
+                        "",
+                        theme.text_style(&font_assets.font_fira, 32.0, theme.colors.accent),
+                    ),
+                    TextSection::new(
+                        strings.format("shop.earned", &[("dust", &upgrades.last_earned)]),
+                        theme.text_style(&font_assets.font_fira, 24.0, theme.colors.text),
+                    ),
+                ]),
+                FaeDustUi,
+            ));
+
+            Upgrade::ALL
+                .iter()
+                .zip(UPGRADE_KEYS.iter())
+                .for_each(|(upgrade, key)| {
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            parent.spawn((
+                                TextBundle::from_section(
+                                    "",
+                                    theme.text_style(
+                                        &font_assets.font_fira,
+                                        28.0,
+                                        theme.colors.text,
+                                    ),
+                                ),
+                                UpgradeUi(*upgrade),
+                            ));
+                            parent.spawn(TextBundle::from_section(
+                                strings.format(
+                                    "shop.upgrade_option",
+                                    &[
+                                        ("key", &key_name(*key)),
+                                        ("description", &upgrade.description(&strings)),
+                                    ],
+                                ),
+                                theme.text_style(&font_assets.font_fira, 16.0, theme.colors.text),
+                            ));
+                        });
+                });
+
+            parent.spawn(TextBundle::from_section(
+                strings.get("shop.launch"),
+                theme.text_style(&font_assets.font_fira, 32.0, theme.colors.positive),
+            ));
+        });
+}
+
+fn buy_upgrades(keyboard_input: Res<Input<KeyCode>>, mut upgrades: ResMut<Upgrades>) {
+    Upgrade::ALL
+        .iter()
+        .zip(UPGRADE_KEYS.iter())
+        .for_each(|(upgrade, key)| {
+            if keyboard_input.just_pressed(*key) {
+                upgrades.buy(*upgrade);
+            }
+        });
+}
+
+fn update_shop_ui(
+    upgrades: Res<Upgrades>,
+    theme: Res<Theme>,
+    strings: Res<Strings>,
+    mut dust_query: Query<&mut Text, (With<FaeDustUi>, Without<UpgradeUi>)>,
+    mut upgrade_query: Query<(&mut Text, &UpgradeUi)>,
+) {
+    dust_query.for_each_mut(|mut text| {
+        text.sections[0].value = strings.format("shop.fae_dust", &[("dust", &upgrades.fae_dust)]);
+    });
+
+    upgrade_query.for_each_mut(|(mut text, upgrade_ui)| {
+        let upgrade = upgrade_ui.0;
+        let level = upgrades.level(upgrade);
+        let section = &mut text.sections[0];
+        let name = upgrade.name(&strings);
+        let max_level = upgrade.max_level();
+
+        match upgrades.next_cost(upgrade) {
+            Some(cost) => {
+                section.value = strings.format(
+                    "shop.upgrade_cost",
+                    &[
+                        ("upgrade", &name),
+                        ("level", &level),
+                        ("max_level", &max_level),
+                        ("cost", &cost),
+                    ],
+                );
+                section.style.color = if cost <= upgrades.fae_dust {
+                    theme.colors.positive
+                } else {
+                    theme.colors.muted
+                };
+            }
+            None => {
+                section.value = strings.format(
+                    "shop.upgrade_max",
+                    &[
+                        ("upgrade", &name),
+                        ("level", &level),
+                        ("max_level", &max_level),
+                    ],
+                );
+                section.style.color = theme.colors.accent;
+            }
+        }
+    });
+}
+
+fn leave_shop(keyboard_input: Res<Input<KeyCode>>, mut transition: ResMut<ScreenTransition>) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        transition.go_to(AppState::CoinLaunch);
+    }
+}
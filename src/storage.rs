@@ -0,0 +1,66 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+pub fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let data = platform::read(key)?;
+
+    match ron::from_str(&data) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            bevy::log::warn!("Discarding unreadable save data for {}: {}", key, err);
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(key: &str, value: &T) {
+    match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(data) => platform::write(key, &data),
+        Err(err) => bevy::log::warn!("Cannot serialize save data for {}: {}", key, err),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::{fs, path::PathBuf};
+
+    const SAVE_DIR: &str = "save";
+
+    fn path(key: &str) -> PathBuf {
+        PathBuf::from(SAVE_DIR).join(format!("{}.ron", key))
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        fs::read_to_string(path(key)).ok()
+    }
+
+    pub fn write(key: &str, data: &str) {
+        if let Err(err) = fs::create_dir_all(SAVE_DIR).and_then(|_| fs::write(path(key), data)) {
+            bevy::log::warn!("Cannot write save data for {}: {}", key, err);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    // wasm: namespace the keys, as localStorage is shared by the whole origin
+    const KEY_PREFIX: &str = "coin_in_the_sky.";
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        storage()?
+            .get_item(&format!("{}{}", KEY_PREFIX, key))
+            .ok()?
+    }
+
+    pub fn write(key: &str, data: &str) {
+        let result =
+            storage().map(|storage| storage.set_item(&format!("{}{}", KEY_PREFIX, key), data));
+
+        if !matches!(result, Some(Ok(_))) {
+            bevy::log::warn!("Cannot write save data for {}", key);
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    locale::Strings,
+    settings::{SettingChangedEvent, Settings},
+};
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Theme::new(&AccessibilityOptions::default()))
+            .add_systems(PreUpdate, apply_accessibility_options);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum TextSize {
+    #[default]
+    Normal,
+    Large,
+    ExtraLarge,
+}
+
+impl TextSize {
+    const ALL: [TextSize; 3] = [TextSize::Normal, TextSize::Large, TextSize::ExtraLarge];
+
+    pub fn next(&self) -> Self {
+        next_in(&Self::ALL, *self)
+    }
+
+    pub fn name<'a>(&self, strings: &'a Strings) -> &'a str {
+        strings.get(match self {
+            TextSize::Normal => "text_size.normal",
+            TextSize::Large => "text_size.large",
+            TextSize::ExtraLarge => "text_size.extra_large",
+        })
+    }
+
+    fn scale(&self) -> f32 {
+        match self {
+            TextSize::Normal => 1.0,
+            TextSize::Large => 1.25,
+            TextSize::ExtraLarge => 1.5,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Palette {
+    #[default]
+    Default,
+    HighContrast,
+    /// Okabe-Ito colours, which stay distinct with the common kinds of colour blindness.
+    ColorBlind,
+}
+
+impl Palette {
+    const ALL: [Palette; 3] = [Palette::Default, Palette::HighContrast, Palette::ColorBlind];
+
+    pub fn next(&self) -> Self {
+        next_in(&Self::ALL, *self)
+    }
+
+    pub fn name<'a>(&self, strings: &'a Strings) -> &'a str {
+        strings.get(match self {
+            Palette::Default => "palette.default",
+            Palette::HighContrast => "palette.high_contrast",
+            Palette::ColorBlind => "palette.color_blind",
+        })
+    }
+
+    fn colors(&self) -> ThemeColors {
+        match self {
+            Palette::Default => ThemeColors {
+                text: Color::WHITE,
+                title: Color::CYAN,
+                accent: Color::YELLOW,
+                positive: Color::GREEN,
+                muted: Color::GRAY,
+                danger: Color::RED,
+                background: Color::rgb(40.0 / 255.0, 40.0 / 255.0, 63.0 / 255.0),
+                hazard: Color::WHITE,
+                hazard_hit: Color::GRAY,
+                pickup: Color::WHITE,
+            },
+            Palette::HighContrast => ThemeColors {
+                text: Color::WHITE,
+                title: Color::WHITE,
+                accent: Color::rgb(1.0, 0.9, 0.0),
+                positive: Color::rgb(0.3, 1.0, 0.3),
+                muted: Color::rgb(0.7, 0.7, 0.7),
+                danger: Color::rgb(1.0, 0.3, 0.3),
+                background: Color::BLACK,
+                hazard: Color::WHITE,
+                hazard_hit: Color::rgb(0.3, 0.3, 0.3),
+                pickup: Color::rgb(1.0, 0.9, 0.0),
+            },
+            Palette::ColorBlind => ThemeColors {
+                text: Color::WHITE,
+                title: Color::rgb(0.34, 0.71, 0.91),
+                accent: Color::rgb(0.9, 0.62, 0.0),
+                positive: Color::rgb(0.34, 0.71, 0.91),
+                muted: Color::GRAY,
+                danger: Color::rgb(0.84, 0.37, 0.0),
+                background: Color::rgb(40.0 / 255.0, 40.0 / 255.0, 63.0 / 255.0),
+                hazard: Color::rgb(1.0, 0.85, 0.7),
+                hazard_hit: Color::rgb(0.6, 0.35, 0.2),
+                pickup: Color::rgb(0.55, 0.8, 1.0),
+            },
+        }
+    }
+}
+
+/// Accessibility choices of the player, the `Theme` follows them.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct AccessibilityOptions {
+    pub text_size: TextSize,
+    pub palette: Palette,
+    /// Turns off animations that are only there for the looks.
+    pub reduced_motion: bool,
+}
+
+#[derive(Clone, Copy)]
+pub struct ThemeColors {
+    pub text: Color,
+    pub title: Color,
+    /// Highlights, e.g. new records and selected options.
+    pub accent: Color,
+    /// Prompts to act and the ingame texts.
+    pub positive: Color,
+    /// Unavailable options.
+    pub muted: Color,
+    pub danger: Color,
+    pub background: Color,
+    /// Tint of the clouds, that slow the coin down.
+    pub hazard: Color,
+    /// Tint of the clouds the coin already hit.
+    pub hazard_hit: Color,
+    /// Tint of the glow around fairies and boosts.
+    pub pickup: Color,
+}
+
+/// Colours and text sizes used by all the UI.
+#[derive(Resource)]
+pub struct Theme {
+    pub colors: ThemeColors,
+    text_scale: f32,
+    pub reduced_motion: bool,
+}
+
+impl Theme {
+    fn new(options: &AccessibilityOptions) -> Self {
+        Self {
+            colors: options.palette.colors(),
+            text_scale: options.text_size.scale(),
+            reduced_motion: options.reduced_motion,
+        }
+    }
+
+    pub fn font_size(&self, size: f32) -> f32 {
+        size * self.text_scale
+    }
+
+    pub fn text_style(&self, font: &Handle<Font>, size: f32, color: Color) -> TextStyle {
+        TextStyle {
+            font: font.clone(),
+            font_size: self.font_size(size),
+            color,
+        }
+    }
+}
+
+fn next_in<T: Copy + PartialEq>(all: &[T], current: T) -> T {
+    let index = all.iter().position(|item| *item == current).unwrap_or(0);
+    all[(index + 1) % all.len()]
+}
+
+/// Run condition for rebuilding a screen after the theme changed while it was shown.
+pub fn theme_changed(theme: Res<Theme>) -> bool {
+    theme.is_changed() && !theme.is_added()
+}
+
+fn apply_accessibility_options(
+    settings: Res<Settings>,
+    mut setting_changed_events: EventReader<SettingChangedEvent>,
+    mut theme: ResMut<Theme>,
+) {
+    if setting_changed_events
+        .iter()
+        .any(|event| event.0.is_accessibility())
+    {
+        *theme = Theme::new(&settings.accessibility);
+    }
+}
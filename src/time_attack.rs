@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::{in_flight, AppState},
+    coin::Coin,
+    game_mode::GameMode,
+    level::LEVEL_TIER_ALTITUDES,
+    locale::Strings,
+    physics::TimeScale,
+    scores::{Scores, TimeAttackRecord},
+    transition::{not_leaving_state, ScreenTransition},
+};
+
+pub struct TimeAttackPlugin;
+
+impl Plugin for TimeAttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TimeAttackRun::default())
+            .add_systems(OnEnter(AppState::Ingame), reset_time_attack_run)
+            .add_systems(
+                Update,
+                (
+                    update_time_attack_run.run_if(in_flight),
+                    check_time_attack_goal.run_if(not_leaving_state),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Ingame))
+                    .run_if(in_time_attack),
+            );
+    }
+}
+
+/// Altitudes (in metres) that can be picked as the time attack goal.
+pub const TIME_ATTACK_GOALS: [u32; 3] = [500, 1000, 2000];
+
+#[derive(Resource, Default)]
+pub struct TimeAttackRun {
+    pub goal: u32,
+    pub elapsed: f32,
+    /// Time taken to reach each of the `split_altitudes`, in order.
+    pub splits: Vec<f32>,
+    pub finished: bool,
+    pub new_best: bool,
+    /// Best run for this goal, as it was before this run started.
+    pub personal_best: Option<TimeAttackRecord>,
+}
+
+impl TimeAttackRun {
+    pub fn split_altitudes(&self) -> impl Iterator<Item = u32> + '_ {
+        LEVEL_TIER_ALTITUDES
+            .iter()
+            .map(|altitude| *altitude as u32)
+            .filter(|altitude| *altitude <= self.goal)
+    }
+
+    pub fn personal_best_split(&self, index: usize) -> Option<f32> {
+        self.personal_best
+            .as_ref()
+            .and_then(|record| record.splits.get(index).copied())
+    }
+
+    /// Describes the split at `index`, compared against the personal best.
+    pub fn describe_split(&self, index: usize, strings: &Strings) -> Option<String> {
+        let altitude = self.split_altitudes().nth(index)?;
+        let split = *self.splits.get(index)?;
+        let time = format!("{:.2}", split);
+
+        Some(match self.personal_best_split(index) {
+            Some(best) => strings.format(
+                "time_attack.split_compared",
+                &[
+                    ("altitude", &altitude),
+                    ("time", &time),
+                    ("delta", &format!("{:+.2}", split - best)),
+                ],
+            ),
+            None => strings.format(
+                "time_attack.split",
+                &[("altitude", &altitude), ("time", &time)],
+            ),
+        })
+    }
+}
+
+fn in_time_attack(game_mode: Res<GameMode>) -> bool {
+    matches!(*game_mode, GameMode::TimeAttack(_))
+}
+
+fn reset_time_attack_run(
+    game_mode: Res<GameMode>,
+    scores: Res<Scores>,
+    mut run: ResMut<TimeAttackRun>,
+) {
+    if let GameMode::TimeAttack(goal) = *game_mode {
+        *run = TimeAttackRun {
+            goal,
+            personal_best: scores.best_times.get(&goal).cloned(),
+            ..Default::default()
+        };
+    }
+}
+
+fn update_time_attack_run(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    coin_query: Query<&Coin>,
+    mut run: ResMut<TimeAttackRun>,
+) {
+    if run.finished {
+        return;
+    }
+
+    let coin = coin_query.single();
+    let altitude = coin.highest_altitude_recorded / 10.0;
+
+    run.elapsed += time_scale.delta_seconds(&time);
+
+    let splits_reached = run
+        .split_altitudes()
+        .filter(|split_altitude| altitude >= *split_altitude as f32)
+        .count();
+    while run.splits.len() < splits_reached {
+        let elapsed = run.elapsed;
+        run.splits.push(elapsed);
+    }
+}
+
+fn check_time_attack_goal(
+    mut run: ResMut<TimeAttackRun>,
+    mut scores: ResMut<Scores>,
+    mut transition: ResMut<ScreenTransition>,
+) {
+    if run.finished || run.splits.len() < run.split_altitudes().count() {
+        return;
+    }
+
+    run.finished = true;
+    run.new_best = scores.register_time_attack(
+        run.goal,
+        TimeAttackRecord {
+            time: run.elapsed,
+            splits: run.splits.clone(),
+        },
+    );
+    transition.go_to(AppState::End);
+}
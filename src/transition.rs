@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+
+use crate::{
+    anim::{Ease, Tween, TweenCompleted, TweenSystems, TweenTarget},
+    app_state::AppState,
+    display::Screen,
+    theme::Theme,
+};
+
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ScreenTransition>()
+            .add_systems(Startup, setup_transition_overlay)
+            .add_systems(
+                Update,
+                (start_transition, advance_transition)
+                    .chain()
+                    .after(TweenSystems),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+enum TransitionPhase {
+    #[default]
+    Idle,
+    /// Covering the screen of the state being left.
+    Out,
+    /// Uncovering the screen of the new state.
+    In,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+enum TransitionStyle {
+    #[default]
+    Fade,
+    /// The overlay rises from the bottom and keeps going up, like flying into the sky.
+    WipeUp,
+    /// The overlay slides in from the right and out to the left.
+    WipeLeft,
+}
+
+impl TransitionStyle {
+    fn between(from: AppState, to: AppState) -> Self {
+        match (from, to) {
+            (AppState::CoinLaunch, AppState::Ingame) => TransitionStyle::WipeUp,
+            (_, AppState::End) => TransitionStyle::WipeLeft,
+            _ => TransitionStyle::Fade,
+        }
+    }
+
+    /// Direction the overlay moves in, in UI coordinates (y down).
+    fn direction(&self) -> Vec2 {
+        match self {
+            TransitionStyle::Fade => Vec2::ZERO,
+            TransitionStyle::WipeUp => Vec2::new(0.0, -1.0),
+            TransitionStyle::WipeLeft => Vec2::new(-1.0, 0.0),
+        }
+    }
+
+    fn tween(&self, phase: TransitionPhase, screen_size: Vec2, color: Color) -> Tween {
+        let travel = -self.direction() * screen_size;
+        let (offsets, colors) = match phase {
+            TransitionPhase::In => ((Vec2::ZERO, -travel), (color, color.with_a(0.0))),
+            _ => ((travel, Vec2::ZERO), (color.with_a(0.0), color)),
+        };
+        // Wipes move an opaque overlay, fades change its opacity in place.
+        let colors = match self {
+            TransitionStyle::Fade => colors,
+            _ => (color, color),
+        };
+
+        Tween::new(
+            TweenTarget::UiOffset(offsets.0, offsets.1),
+            TRANSITION_HALF_SECONDS,
+            Ease::QuadInOut,
+        )
+        .and(TweenTarget::BackgroundColor(colors.0, colors.1))
+    }
+}
+
+const TRANSITION_HALF_SECONDS: f32 = 0.3;
+
+/// Animates switching from one `AppState` to another; the screen being left is only cleaned up
+/// once it is fully covered.
+#[derive(Resource, Default)]
+pub struct ScreenTransition {
+    target: Option<AppState>,
+    phase: TransitionPhase,
+    style: TransitionStyle,
+}
+
+impl ScreenTransition {
+    /// Starts a transition to `state`, unless one is already running.
+    pub fn go_to(&mut self, state: AppState) {
+        if self.target.is_none() && self.phase == TransitionPhase::Idle {
+            self.target = Some(state);
+        }
+    }
+}
+
+/// Run condition for systems that must stop once the current state is being left, e.g. the
+/// ones ending a flight.
+pub fn not_leaving_state(transition: Res<ScreenTransition>) -> bool {
+    transition.target.is_none()
+}
+
+#[derive(Component)]
+struct TransitionOverlay;
+
+fn setup_transition_overlay(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..Default::default()
+            },
+            background_color: Color::NONE.into(),
+            z_index: ZIndex::Global(i32::MAX),
+            ..Default::default()
+        },
+        TransitionOverlay,
+    ));
+}
+
+fn start_transition(
+    mut commands: Commands,
+    mut transition: ResMut<ScreenTransition>,
+    state: Res<State<AppState>>,
+    theme: Res<Theme>,
+    screen: Res<Screen>,
+    overlay_query: Query<Entity, With<TransitionOverlay>>,
+) {
+    let target = match transition.target {
+        Some(target) if transition.phase == TransitionPhase::Idle => target,
+        _ => return,
+    };
+    let overlay = match overlay_query.get_single() {
+        Ok(overlay) => overlay,
+        Err(_) => return,
+    };
+
+    let style = TransitionStyle::between(*state.get(), target);
+    commands.entity(overlay).insert(style.tween(
+        TransitionPhase::Out,
+        screen.size,
+        theme.colors.background,
+    ));
+    transition.phase = TransitionPhase::Out;
+    transition.style = style;
+}
+
+fn advance_transition(
+    mut commands: Commands,
+    mut transition: ResMut<ScreenTransition>,
+    mut tween_completed_events: EventReader<TweenCompleted>,
+    mut next_state: ResMut<NextState<AppState>>,
+    theme: Res<Theme>,
+    screen: Res<Screen>,
+    mut overlay_query: Query<(Entity, &mut Style, &mut BackgroundColor), With<TransitionOverlay>>,
+) {
+    let (overlay, mut style, mut background) = match overlay_query.get_single_mut() {
+        Ok(overlay) => overlay,
+        Err(_) => return,
+    };
+    if !tween_completed_events
+        .iter()
+        .any(|event| event.entity == overlay)
+    {
+        return;
+    }
+
+    match transition.phase {
+        TransitionPhase::Out => {
+            if let Some(target) = transition.target.take() {
+                next_state.set(target);
+            }
+            commands.entity(overlay).insert(transition.style.tween(
+                TransitionPhase::In,
+                screen.size,
+                theme.colors.background,
+            ));
+            transition.phase = TransitionPhase::In;
+        }
+        TransitionPhase::In => {
+            // a wipe parks the overlay off-screen, where a bigger window would show it
+            style.left = Val::Px(0.0);
+            style.top = Val::Px(0.0);
+            background.0 = Color::NONE;
+            transition.phase = TransitionPhase::Idle;
+        }
+        TransitionPhase::Idle => {}
+    }
+}
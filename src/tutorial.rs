@@ -0,0 +1,442 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    anim::{Ease, Tween, TweenTarget},
+    app_state::{AppState, StateOwner},
+    cloud::{spawn_cloud, Cloud, CloudHitEvent},
+    coin::{Coin, ManualBoostUsedEvent},
+    coin_camera::COIN_SCREEN_BOUNDS_Y,
+    fairy::{spawn_fairy, Fairy, FairyTouchedEvent},
+    game_assets::FontAssets,
+    game_mode::GameMode,
+    locale::Strings,
+    player::{key_name, PlayerControls, PlayerId},
+    pool::PoolSpawner,
+    storage,
+    theme::Theme,
+};
+
+pub struct TutorialPlugin;
+
+impl Plugin for TutorialPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TutorialProgress::load())
+            .insert_resource(TutorialRun::default())
+            .add_systems(
+                OnEnter(AppState::Ingame),
+                setup_tutorial_run.run_if(in_tutorial),
+            )
+            .add_systems(OnExit(AppState::Ingame), resume_simulation)
+            .add_systems(
+                Update,
+                (
+                    keep_coin_cruising,
+                    spawn_tutorial_objects,
+                    prompt_tutorial_step,
+                    complete_tutorial_steps,
+                    finish_tutorial,
+                    update_tutorial_prompt_ui,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Ingame))
+                    .run_if(in_tutorial),
+            );
+    }
+}
+
+/// Whether the first flight tutorial has been completed (or skipped).
+#[derive(Resource, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct TutorialProgress {
+    pub completed: bool,
+}
+
+const TUTORIAL_SAVE_KEY: &str = "tutorial";
+
+impl TutorialProgress {
+    fn load() -> Self {
+        storage::load(TUTORIAL_SAVE_KEY).unwrap_or_default()
+    }
+
+    pub fn complete(&mut self) {
+        if !self.completed {
+            self.completed = true;
+            storage::save(TUTORIAL_SAVE_KEY, self);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+enum TutorialStep {
+    #[default]
+    Dodge,
+    TouchFairy,
+    UseBoost,
+    Done,
+}
+
+impl TutorialStep {
+    const COUNT: usize = 3;
+
+    fn index(&self) -> usize {
+        match self {
+            TutorialStep::Dodge => 0,
+            TutorialStep::TouchFairy => 1,
+            TutorialStep::UseBoost => 2,
+            TutorialStep::Done => Self::COUNT,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            TutorialStep::Dodge => TutorialStep::TouchFairy,
+            TutorialStep::TouchFairy => TutorialStep::UseBoost,
+            TutorialStep::UseBoost | TutorialStep::Done => TutorialStep::Done,
+        }
+    }
+
+    fn prompt(&self, controls: &PlayerControls, strings: &Strings) -> String {
+        let key = match self {
+            TutorialStep::Dodge => "tutorial.dodge",
+            TutorialStep::TouchFairy => "tutorial.touch_fairy",
+            TutorialStep::UseBoost => "tutorial.use_boost",
+            TutorialStep::Done => "tutorial.done",
+        };
+
+        strings.format(
+            key,
+            &[
+                ("left", &key_name(controls.left)),
+                ("right", &key_name(controls.right)),
+                ("boost", &key_name(controls.boost)),
+            ],
+        )
+    }
+
+    /// Keys that resume the flight once the step is prompted.
+    fn resume_keys(&self, controls: &PlayerControls) -> Vec<KeyCode> {
+        match self {
+            TutorialStep::Dodge | TutorialStep::TouchFairy => vec![controls.left, controls.right],
+            TutorialStep::UseBoost => vec![controls.boost],
+            TutorialStep::Done => vec![],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+enum StepPhase {
+    /// Flying until the altitude where the step starts.
+    #[default]
+    Waiting,
+    /// The step's object is coming into view.
+    Approaching,
+    /// The simulation is paused until the player reacts to the prompt.
+    Prompted,
+    /// Waiting for the step to actually be done.
+    Acting,
+}
+
+/// Progress through the scripted first flight.
+#[derive(Resource, Default)]
+struct TutorialRun {
+    step: TutorialStep,
+    phase: StepPhase,
+    next_step_altitude: f32,
+}
+
+/// The tutorial coin keeps this speed until all the steps are done, so nothing is rushed.
+const TUTORIAL_CRUISE_SPEED: f32 = 500.0;
+/// Altitude (in world units) flown between two steps.
+const TUTORIAL_STEP_GAP: f32 = 600.0;
+const TUTORIAL_SPAWN_Y: f32 = COIN_SCREEN_BOUNDS_Y * 1.5;
+/// The step is prompted once its object is this close above the coin.
+const TUTORIAL_PROMPT_DISTANCE: f32 = 220.0;
+/// Horizontal distance between the coin and the fairy it has to reach.
+const TUTORIAL_FAIRY_OFFSET_X: f32 = 160.0;
+const TUTORIAL_DONE_PROMPT_SECONDS: f32 = 3.0;
+const TUTORIAL_DONE_FADE_SECONDS: f32 = 1.0;
+
+const TUTORIAL_PLAYER: PlayerId = PlayerId(0);
+
+fn in_tutorial(game_mode: Res<GameMode>) -> bool {
+    *game_mode == GameMode::Tutorial
+}
+
+#[derive(Component)]
+struct TutorialPromptUi;
+
+#[derive(Component)]
+struct TutorialStepUi;
+
+fn setup_tutorial_run(
+    mut commands: Commands,
+    font_assets: Res<FontAssets>,
+    theme: Res<Theme>,
+    mut run: ResMut<TutorialRun>,
+) {
+    *run = TutorialRun {
+        next_step_altitude: TUTORIAL_STEP_GAP,
+        ..Default::default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(30.0),
+                    width: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            StateOwner(AppState::Ingame),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    theme.text_style(&font_assets.font_fira, 20.0, theme.colors.text),
+                ),
+                TutorialStepUi,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    theme.text_style(&font_assets.font_fira, 32.0, theme.colors.accent),
+                ),
+                TutorialPromptUi,
+            ));
+        });
+}
+
+fn resume_simulation(mut time: ResMut<Time>) {
+    if time.is_paused() {
+        time.unpause();
+    }
+}
+
+fn keep_coin_cruising(run: Res<TutorialRun>, mut query: Query<&mut Coin>) {
+    if run.step == TutorialStep::Done {
+        return;
+    }
+
+    query.for_each_mut(|mut coin| {
+        coin.speed = TUTORIAL_CRUISE_SPEED;
+    });
+}
+
+fn spawn_tutorial_objects(
+    mut spawner: PoolSpawner,
+    mut run: ResMut<TutorialRun>,
+    coin_query: Query<(&Coin, &Transform)>,
+) {
+    if run.phase != StepPhase::Waiting {
+        return;
+    }
+    let (coin, coin_transform) = match coin_query.get_single() {
+        Ok(coin) => coin,
+        Err(_) => return,
+    };
+    if coin.altitude < run.next_step_altitude {
+        return;
+    }
+
+    let coin_x = coin_transform.translation.x;
+    match run.step {
+        TutorialStep::Dodge => {
+            // right in the way of the coin
+            spawn_cloud(
+                &mut spawner,
+                Vec2::new(coin_x, TUTORIAL_SPAWN_Y),
+                TUTORIAL_PLAYER,
+            );
+            run.phase = StepPhase::Approaching;
+        }
+        TutorialStep::TouchFairy => {
+            // towards the middle, so it can always be reached
+            let offset = if coin_x > 0.0 {
+                -TUTORIAL_FAIRY_OFFSET_X
+            } else {
+                TUTORIAL_FAIRY_OFFSET_X
+            };
+            spawn_fairy(
+                &mut spawner,
+                Vec2::new(coin_x + offset, TUTORIAL_SPAWN_Y),
+                TUTORIAL_PLAYER,
+            );
+            run.phase = StepPhase::Approaching;
+        }
+        TutorialStep::UseBoost => run.phase = StepPhase::Approaching,
+        TutorialStep::Done => {}
+    }
+}
+
+/// Pauses the flight and shows the prompt as soon as the step's object is close enough.
+fn prompt_tutorial_step(
+    mut time: ResMut<Time>,
+    mut run: ResMut<TutorialRun>,
+    coin_query: Query<&Transform, With<Coin>>,
+    cloud_query: Query<(&Cloud, &Transform)>,
+    fairy_query: Query<&Transform, With<Fairy>>,
+) {
+    if run.phase != StepPhase::Approaching {
+        return;
+    }
+    let coin_y = match coin_query.get_single() {
+        Ok(transform) => transform.translation.y,
+        Err(_) => return,
+    };
+
+    let is_close = |transform: &Transform| {
+        let distance = transform.translation.y - coin_y;
+        distance > 0.0 && distance < TUTORIAL_PROMPT_DISTANCE
+    };
+    let ready = match run.step {
+        TutorialStep::Dodge => cloud_query
+            .iter()
+            .any(|(cloud, transform)| cloud.is_active() && is_close(transform)),
+        TutorialStep::TouchFairy => fairy_query.iter().any(is_close),
+        TutorialStep::UseBoost => true,
+        TutorialStep::Done => false,
+    };
+
+    if ready {
+        time.pause();
+        run.phase = StepPhase::Prompted;
+    }
+}
+
+/// Events that can complete or fail a step.
+#[derive(SystemParam)]
+struct TutorialEvents<'w, 's> {
+    cloud_hits: EventReader<'w, 's, CloudHitEvent>,
+    fairies_touched: EventReader<'w, 's, FairyTouchedEvent>,
+    boosts_used: EventReader<'w, 's, ManualBoostUsedEvent>,
+}
+
+fn complete_tutorial_steps(
+    keyboard: Res<Input<KeyCode>>,
+    mut time: ResMut<Time>,
+    mut run: ResMut<TutorialRun>,
+    mut events: TutorialEvents,
+    coin_query: Query<(&Coin, &Transform, &PlayerControls)>,
+    cloud_query: Query<(&Cloud, &Transform)>,
+    fairy_query: Query<&Transform, With<Fairy>>,
+) {
+    let (coin, coin_transform, controls) = match coin_query.get_single() {
+        Ok(coin) => coin,
+        Err(_) => return,
+    };
+
+    if run.phase == StepPhase::Prompted && keyboard.any_just_pressed(run.step.resume_keys(controls))
+    {
+        time.unpause();
+        run.phase = StepPhase::Acting;
+    }
+
+    let cloud_hit = events.cloud_hits.iter().count() > 0;
+    let fairy_touched = events.fairies_touched.iter().count() > 0;
+    let boost_used = events.boosts_used.iter().count() > 0;
+    if run.phase != StepPhase::Acting {
+        return;
+    }
+
+    let coin_y = coin_transform.translation.y;
+    let (done, failed) = match run.step {
+        TutorialStep::Dodge => {
+            let cloud_ahead = cloud_query
+                .iter()
+                .any(|(cloud, transform)| cloud.is_active() && transform.translation.y > coin_y);
+            (!cloud_hit && !cloud_ahead, cloud_hit)
+        }
+        TutorialStep::TouchFairy => {
+            let fairy_ahead = fairy_query
+                .iter()
+                .any(|transform| transform.translation.y > coin_y);
+            (fairy_touched, !fairy_touched && !fairy_ahead)
+        }
+        TutorialStep::UseBoost => (boost_used, false),
+        TutorialStep::Done => (false, false),
+    };
+
+    if failed {
+        // try again with a fresh object right away
+        run.phase = StepPhase::Waiting;
+        run.next_step_altitude = coin.altitude;
+    } else if done {
+        run.step = run.step.next();
+        run.phase = StepPhase::Waiting;
+        run.next_step_altitude = coin.altitude + TUTORIAL_STEP_GAP;
+    }
+}
+
+/// Remembers the completion, and fades the last prompt out after a while.
+fn finish_tutorial(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    run: Res<TutorialRun>,
+    mut progress: ResMut<TutorialProgress>,
+    prompt_query: Query<Entity, With<TutorialPromptUi>>,
+) {
+    if !run.is_changed() || run.step != TutorialStep::Done {
+        return;
+    }
+
+    progress.complete();
+    prompt_query.for_each(|entity| {
+        commands.entity(entity).insert(
+            Tween::new(
+                TweenTarget::TextColor(theme.colors.accent, theme.colors.accent.with_a(0.0)),
+                TUTORIAL_DONE_FADE_SECONDS,
+                Ease::Linear,
+            )
+            .with_delay(TUTORIAL_DONE_PROMPT_SECONDS),
+        );
+    });
+}
+
+fn update_tutorial_prompt_ui(
+    run: Res<TutorialRun>,
+    strings: Res<Strings>,
+    controls_query: Query<&PlayerControls>,
+    mut prompt_query: Query<&mut Text, (With<TutorialPromptUi>, Without<TutorialStepUi>)>,
+    mut step_query: Query<&mut Text, With<TutorialStepUi>>,
+) {
+    if !run.is_changed() {
+        return;
+    }
+    let controls = match controls_query.get_single() {
+        Ok(controls) => controls,
+        Err(_) => return,
+    };
+
+    let shown = run.step == TutorialStep::Done
+        || matches!(run.phase, StepPhase::Prompted | StepPhase::Acting);
+
+    prompt_query.for_each_mut(|mut text| {
+        text.sections[0].value = if shown {
+            run.step.prompt(controls, &strings)
+        } else {
+            String::new()
+        };
+    });
+
+    step_query.for_each_mut(|mut text| {
+        text.sections[0].value = if run.step == TutorialStep::Done {
+            String::new()
+        } else {
+            strings.format(
+                "tutorial.step",
+                &[
+                    ("step", &(run.step.index() + 1)),
+                    ("count", &TutorialStep::COUNT),
+                ],
+            )
+        };
+    });
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    game_mode::{DailyChallenge, GameMode},
+    locale::Strings,
+    scores::Scores,
+    storage,
+};
+
+pub struct UpgradesPlugin;
+
+impl Plugin for UpgradesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Upgrades::load())
+            .add_systems(OnEnter(AppState::End), award_fae_dust);
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Upgrade {
+    ExtraBoosts,
+    BoostPower,
+    CloudArmor,
+    WideSweetSpot,
+}
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 4] = [
+        Upgrade::ExtraBoosts,
+        Upgrade::BoostPower,
+        Upgrade::CloudArmor,
+        Upgrade::WideSweetSpot,
+    ];
+
+    pub fn name<'a>(&self, strings: &'a Strings) -> &'a str {
+        strings.get(match self {
+            Upgrade::ExtraBoosts => "upgrade.extra_boosts",
+            Upgrade::BoostPower => "upgrade.boost_power",
+            Upgrade::CloudArmor => "upgrade.cloud_armor",
+            Upgrade::WideSweetSpot => "upgrade.wide_sweet_spot",
+        })
+    }
+
+    pub fn description<'a>(&self, strings: &'a Strings) -> &'a str {
+        strings.get(match self {
+            Upgrade::ExtraBoosts => "upgrade.extra_boosts.description",
+            Upgrade::BoostPower => "upgrade.boost_power.description",
+            Upgrade::CloudArmor => "upgrade.cloud_armor.description",
+            Upgrade::WideSweetSpot => "upgrade.wide_sweet_spot.description",
+        })
+    }
+
+    pub fn max_level(&self) -> u32 {
+        3
+    }
+
+    /// Fae dust needed to buy the level after `level`.
+    pub fn cost(&self, level: u32) -> u32 {
+        let base = match self {
+            Upgrade::ExtraBoosts => 60,
+            Upgrade::BoostPower => 50,
+            Upgrade::CloudArmor => 40,
+            Upgrade::WideSweetSpot => 30,
+        };
+        base * (level + 1)
+    }
+}
+
+/// Share of each metre climbed that is turned into fae dust.
+const FAE_DUST_PER_METRE: f32 = 0.1;
+
+const BOOST_POWER_PER_LEVEL: f32 = 50.0;
+const CLOUD_ARMOR_PER_LEVEL: f32 = 0.15;
+const SWEET_SPOT_BASE_WIDTH: f32 = 0.05;
+const SWEET_SPOT_PER_LEVEL: f32 = 0.05;
+
+#[derive(Resource, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct Upgrades {
+    pub fae_dust: u32,
+    levels: HashMap<Upgrade, u32>,
+    /// Fae dust earned by the last run.
+    #[serde(skip)]
+    pub last_earned: u32,
+}
+
+const UPGRADES_SAVE_KEY: &str = "upgrades";
+
+impl Upgrades {
+    fn load() -> Self {
+        storage::load(UPGRADES_SAVE_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        storage::save(UPGRADES_SAVE_KEY, self);
+    }
+
+    pub fn level(&self, upgrade: Upgrade) -> u32 {
+        self.levels.get(&upgrade).copied().unwrap_or_default()
+    }
+
+    /// Cost of the next level, or `None` once the upgrade is maxed out.
+    pub fn next_cost(&self, upgrade: Upgrade) -> Option<u32> {
+        let level = self.level(upgrade);
+        (level < upgrade.max_level()).then(|| upgrade.cost(level))
+    }
+
+    /// Returns whether the upgrade could be afforded.
+    pub fn buy(&mut self, upgrade: Upgrade) -> bool {
+        let cost = match self.next_cost(upgrade) {
+            Some(cost) if cost <= self.fae_dust => cost,
+            _ => return false,
+        };
+
+        self.fae_dust -= cost;
+        *self.levels.entry(upgrade).or_default() += 1;
+        self.save();
+
+        true
+    }
+
+    /// Returns whether there was enough fae dust.
+    pub fn spend(&mut self, cost: u32) -> bool {
+        if cost > self.fae_dust {
+            return false;
+        }
+
+        self.fae_dust -= cost;
+        self.save();
+
+        true
+    }
+
+    pub fn extra_boosts(&self) -> i32 {
+        self.level(Upgrade::ExtraBoosts) as i32
+    }
+
+    pub fn boost_power_bonus(&self) -> f32 {
+        self.level(Upgrade::BoostPower) as f32 * BOOST_POWER_PER_LEVEL
+    }
+
+    /// Factor applied to the speed lost when hitting a cloud.
+    pub fn cloud_penalty_factor(&self) -> f32 {
+        1.0 - self.level(Upgrade::CloudArmor) as f32 * CLOUD_ARMOR_PER_LEVEL
+    }
+
+    /// Launches at or above this percentage go out at full speed.
+    pub fn launch_sweet_spot(&self) -> f32 {
+        1.0 - SWEET_SPOT_BASE_WIDTH
+            - self.level(Upgrade::WideSweetSpot) as f32 * SWEET_SPOT_PER_LEVEL
+    }
+}
+
+/// Only ranked runs earn dust, so replaying practice runs or races can't farm it.
+fn award_fae_dust(
+    scores: Res<Scores>,
+    game_mode: Res<GameMode>,
+    daily: Res<DailyChallenge>,
+    mut upgrades: ResMut<Upgrades>,
+) {
+    let ranked = match *game_mode {
+        GameMode::Endless => true,
+        GameMode::Daily => daily.scored,
+        _ => false,
+    };
+    if !ranked {
+        upgrades.last_earned = 0;
+        return;
+    }
+
+    let earned = (scores.end_altitude.max(0) as f32 * FAE_DUST_PER_METRE).floor() as u32;
+
+    upgrades.last_earned = earned;
+    if earned > 0 {
+        upgrades.fae_dust += earned;
+        upgrades.save();
+    }
+}